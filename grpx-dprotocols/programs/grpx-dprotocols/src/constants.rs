@@ -1,2 +1,19 @@
 pub const COLLECTION_AUTHORITY_SEED: &[u8] = b"authority";
 pub const ANCHOR_DISCRIMINATOR: usize = 8;
+pub const MAX_BUNDLE_MINTS: usize = 11;
+pub const BUNDLE_ACCOUNTS_PER_MINT: usize = 3;
+pub const MAX_BARTER_MINTS: usize = 4;
+pub const BARTER_PICK_ACCOUNTS: usize = 4;
+pub const MAX_WINE_ATTRIBUTES: usize = 8;
+pub const MAX_ATTRIBUTE_LEN: usize = 32;
+pub const MAX_CUSTODY_HOPS: usize = 16;
+pub const MAX_GEOHASH_PREFIXES: usize = 4;
+pub const MAX_GEOHASH_LEN: usize = 12;
+pub const MAX_SHIPPING_DETAILS_LEN: usize = 512;
+pub const AUCTION_EXTENSION_SECONDS: i64 = 300;
+pub const DISPUTE_WINDOW_SECONDS: i64 = 3 * 24 * 60 * 60;
+pub const DELIVERY_TIMEOUT_SECONDS: i64 = 30 * 24 * 60 * 60;
+pub const DELIVERY_CODE_TTL_SECONDS: i64 = 14 * 24 * 60 * 60;
+pub const DELIVERY_ATTESTATION_PREFIX: &[u8] = b"grpx:delivered";
+pub const BREACH_REPORT_PREFIX: &[u8] = b"grpx:breach";
+pub const LOCATION_ATTESTATION_PREFIX: &[u8] = b"grpx:location";
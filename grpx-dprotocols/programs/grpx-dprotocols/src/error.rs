@@ -22,4 +22,139 @@ pub enum GrpxProtocolError {
 
     #[msg("The offer has already been completed or refunded")]
     OfferAlreadySettled,
+
+    #[msg("Creator royalty account does not match the metadata creators")]
+    InvalidCreatorAccount,
+
+    #[msg("Payment accounts do not match the offer's payment mint")]
+    InvalidPaymentAccounts,
+
+    #[msg("Bundle accounts do not match the offer's bundled mints")]
+    InvalidBundleAccounts,
+
+    #[msg("Too many NFTs in a single bundle offer")]
+    BundleTooLarge,
+
+    #[msg("Requested quantity is not available on this offer")]
+    InvalidQuantity,
+
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+
+    #[msg("Bid amount must be greater than zero")]
+    InvalidBidAmount,
+
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+
+    #[msg("The bid has expired")]
+    BidExpired,
+
+    #[msg("NFT is not a verified member of the bid's collection")]
+    NotInCollection,
+
+    #[msg("NFT does not carry the bid's required attribute")]
+    AttributeMismatch,
+
+    #[msg("Bid is below the reserve price or minimum increment")]
+    BidTooLow,
+
+    #[msg("The auction has ended")]
+    AuctionEnded,
+
+    #[msg("The auction has not ended yet")]
+    AuctionNotEnded,
+
+    #[msg("The auction has no bids")]
+    NoAuctionBids,
+
+    #[msg("The auction already has bids")]
+    AuctionHasBids,
+
+    #[msg("Operation not allowed in the current tender phase")]
+    InvalidTenderPhase,
+
+    #[msg("Revealed bid does not match the commitment")]
+    InvalidReveal,
+
+    #[msg("The tender has no valid bids")]
+    NoTenderBids,
+
+    #[msg("The tender has not been settled yet")]
+    TenderNotSettled,
+
+    #[msg("The offer has expired")]
+    OfferExpired,
+
+    #[msg("The offer was amended since this version")]
+    OfferVersionMismatch,
+
+    #[msg("Release share must be at most 10000 basis points")]
+    InvalidReleaseSchedule,
+
+    #[msg("Delivery milestone is out of order")]
+    InvalidMilestone,
+
+    #[msg("Missing or invalid ed25519 attestation")]
+    InvalidAttestation,
+
+    #[msg("The dispute window has closed")]
+    DisputeWindowClosed,
+
+    #[msg("Invalid cold-chain thresholds or penalty schedule")]
+    InvalidColdChainTerms,
+
+    #[msg("Delivery code does not match")]
+    InvalidDeliveryCode,
+
+    #[msg("Signer is not the current custodian, or hands off to itself")]
+    InvalidCustodian,
+
+    #[msg("Custody chain has no room for another handoff")]
+    CustodyChainFull,
+
+    #[msg("The consumer is not the final custodian")]
+    CustodyNotTransferred,
+
+    #[msg("Invalid geofence")]
+    InvalidGeofence,
+
+    #[msg("Location is outside the offer's geofence")]
+    OutsideGeofence,
+
+    #[msg("Delivery location has not been verified")]
+    LocationNotVerified,
+
+    #[msg("Shipping details are too large")]
+    ShippingDetailsTooLarge,
+
+    #[msg("This NFT already has an active listing")]
+    ListingTaken,
+
+    #[msg("Listing account missing or does not match the offer")]
+    InvalidListing,
+
+    #[msg("Offer has not expired")]
+    OfferNotExpired,
+
+    #[msg("Batch accounts do not match the listed offers")]
+    InvalidBatchAccounts,
+
+    #[msg("Offer has delivery terms and cannot be bought instantly")]
+    DeliveryRequired,
+
+    #[msg("Invalid barter terms")]
+    InvalidBarterTerms,
+
+    #[msg("Barter accounts do not match the NFTs the offer asks for")]
+    InvalidBarterAccounts,
+
+    #[msg("Barter offers cannot be countered")]
+    BarterNotNegotiable,
+
+    #[msg("The delivery code has expired")]
+    DeliveryCodeExpired,
+
+    #[msg("Token A must be a single NFT")]
+    InvalidNftMint,
 }
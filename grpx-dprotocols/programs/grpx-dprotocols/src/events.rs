@@ -0,0 +1,95 @@
+use crate::state::OfferStatus;
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct CollectionCreated {
+    pub collection_mint: Pubkey,
+    pub owner: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+}
+
+#[event]
+pub struct NftMinted {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub collection_mint: Pubkey,
+    pub name: String,
+    pub uri: String,
+}
+
+#[event]
+pub struct CollectionVerified {
+    pub mint: Pubkey,
+    pub collection_mint: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct OfferOpened {
+    pub offer: Pubkey,
+    pub id: u64,
+    pub producer: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_a_offered_amount: u64,
+    pub token_b_desired_amount: u64,
+    pub expires_at: Option<i64>,
+    pub status: OfferStatus,
+}
+
+#[event]
+pub struct OfferAccepted {
+    pub offer: Pubkey,
+    pub producer: Pubkey,
+    pub consumer: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub quantity: u64,
+    pub token_b_amount: u64,
+    pub version: u32,
+    pub status: OfferStatus,
+}
+
+#[event]
+pub struct OfferConfirmed {
+    pub offer: Pubkey,
+    pub producer: Pubkey,
+    pub consumer: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub quantity: u64,
+    pub token_b_amount: u64,
+    pub invoice_number: u64,
+    pub token_a_remaining_amount: u64,
+    pub status: OfferStatus,
+}
+
+#[event]
+pub struct OfferBought {
+    pub offer: Pubkey,
+    pub producer: Pubkey,
+    pub consumer: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub quantity: u64,
+    pub token_b_amount: u64,
+    pub invoice_number: u64,
+    pub token_a_remaining_amount: u64,
+    pub status: OfferStatus,
+}
+
+#[event]
+pub struct OfferRefunded {
+    pub offer: Pubkey,
+    pub producer: Pubkey,
+    pub consumer: Option<Pubkey>,
+    pub initiator: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    pub status: OfferStatus,
+}
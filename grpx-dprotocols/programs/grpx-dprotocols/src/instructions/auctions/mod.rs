@@ -0,0 +1,9 @@
+pub mod outbid;
+pub mod settle;
+pub mod start;
+pub mod withdraw;
+
+pub use outbid::*;
+pub use settle::*;
+pub use start::*;
+pub use withdraw::*;
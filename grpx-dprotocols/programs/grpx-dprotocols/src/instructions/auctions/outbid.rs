@@ -0,0 +1,149 @@
+use crate::error::GrpxProtocolError;
+use crate::state::Auction;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct PlaceAuctionBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(mut)]
+    pub previous_bidder: Option<SystemAccount<'info>>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub previous_bidder_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = token_mint_b,
+        constraint = !auction.has_ended(Clock::get()?.unix_timestamp) @ GrpxProtocolError::AuctionEnded,
+        seeds = [b"auction", auction.producer.as_ref(), auction.id.to_le_bytes().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = auction,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceAuctionBid<'info> {
+    pub fn check_bid_amount(&self, amount: u64) -> Result<()> {
+        require!(
+            amount >= self.auction.minimum_bid()?,
+            GrpxProtocolError::BidTooLow
+        );
+
+        Ok(())
+    }
+
+    pub fn refund_previous_bidder(&mut self) -> Result<()> {
+        let Some(highest_bidder) = self.auction.highest_bidder else {
+            return Ok(());
+        };
+
+        let previous_bidder = self
+            .previous_bidder
+            .as_ref()
+            .filter(|previous_bidder| previous_bidder.key() == highest_bidder)
+            .ok_or(GrpxProtocolError::InvalidPaymentAccounts)?;
+
+        if self.auction.is_native() {
+            self.auction.sub_lamports(self.auction.highest_bid)?;
+            previous_bidder.add_lamports(self.auction.highest_bid)?;
+            return Ok(());
+        }
+
+        let (Some(previous_bidder_token_account_b), Some(vault_token_account_b)) = (
+            &self.previous_bidder_token_account_b,
+            &self.vault_token_account_b,
+        ) else {
+            return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+        };
+        require!(
+            previous_bidder_token_account_b.owner == highest_bidder
+                && previous_bidder_token_account_b.mint == self.token_mint_b.key(),
+            GrpxProtocolError::InvalidPaymentAccounts
+        );
+
+        let seeds = &[
+            b"auction",
+            self.auction.producer.as_ref(),
+            &self.auction.id.to_le_bytes()[..],
+            &[self.auction.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        let accounts = TransferChecked {
+            from: vault_token_account_b.to_account_info(),
+            mint: self.token_mint_b.to_account_info(),
+            to: previous_bidder_token_account_b.to_account_info(),
+            authority: self.auction.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        transfer_checked(
+            cpi_context,
+            self.auction.highest_bid,
+            self.token_mint_b.decimals,
+        )
+    }
+
+    pub fn deposit_bid(&mut self, amount: u64) -> Result<()> {
+        if self.auction.is_native() {
+            let transfer_accounts = Transfer {
+                from: self.bidder.to_account_info(),
+                to: self.auction.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), transfer_accounts);
+            transfer(cpi_ctx, amount)?;
+        } else {
+            let (Some(bidder_token_account_b), Some(vault_token_account_b)) =
+                (&self.bidder_token_account_b, &self.vault_token_account_b)
+            else {
+                return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+            };
+
+            let transfer_accounts = TransferChecked {
+                from: bidder_token_account_b.to_account_info(),
+                mint: self.token_mint_b.to_account_info(),
+                to: vault_token_account_b.to_account_info(),
+                authority: self.bidder.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+            transfer_checked(cpi_ctx, amount, self.token_mint_b.decimals)?;
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        self.auction.highest_bidder = Some(self.bidder.key());
+        self.auction.highest_bid = amount;
+        self.auction.extend_if_closing(now);
+
+        Ok(())
+    }
+}
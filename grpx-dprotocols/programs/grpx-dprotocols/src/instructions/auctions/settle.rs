@@ -0,0 +1,219 @@
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::{
+    error::GrpxProtocolError,
+    state::{Auction, Offer, OfferStatus, ProducerCounter},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    #[account(
+        mut,
+        constraint = (
+            initiator.key() == auction.producer ||
+            auction.highest_bidder == Some(initiator.key())
+        ) @ GrpxProtocolError::Unauthorized
+    )]
+    pub initiator: Signer<'info>,
+
+    #[account(mut)]
+    pub producer: SystemAccount<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        close = producer,
+        has_one = producer,
+        has_one = token_mint_a,
+        has_one = token_mint_b,
+        constraint = auction.has_ended(Clock::get()?.unix_timestamp) @ GrpxProtocolError::AuctionNotEnded,
+        constraint = auction.highest_bidder.is_some() @ GrpxProtocolError::NoAuctionBids,
+        seeds = [b"auction", producer.key().as_ref(), auction.id.to_le_bytes().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = auction,
+        associated_token::token_program = token_program,
+    )]
+    pub auction_vault_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = auction,
+        associated_token::token_program = token_program,
+    )]
+    pub auction_vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = initiator,
+        space = ANCHOR_DISCRIMINATOR + ProducerCounter::INIT_SPACE,
+        seeds = [b"producer_counter", producer.key().as_ref()],
+        bump
+    )]
+    pub producer_counter: Account<'info, ProducerCounter>,
+
+    #[account(
+        init,
+        payer = initiator,
+        space = ANCHOR_DISCRIMINATOR + Offer::INIT_SPACE,
+        seeds = [b"offer", producer.key().as_ref(), producer_counter.offer_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        init,
+        payer = initiator,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = initiator,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleAuction<'info> {
+    pub fn open_accepted_offer(&mut self, bumps: &SettleAuctionBumps) -> Result<()> {
+        let id = self
+            .producer_counter
+            .assign_offer_id(self.producer.key(), bumps.producer_counter)?;
+
+        self.offer.set_inner(Offer {
+            id,
+            producer: self.producer.key(),
+            consumer: self.auction.highest_bidder,
+            token_mint_a: self.token_mint_a.key(),
+            token_mint_b: self.token_mint_b.key(),
+            token_a_offered_amount: 1,
+            token_a_remaining_amount: 1,
+            token_a_accepted_amount: 1,
+            token_b_desired_amount: self.auction.highest_bid,
+            awarded: true,
+            status: OfferStatus::Accepted,
+            bump: bumps.offer,
+            ..Default::default()
+        });
+
+        Ok(())
+    }
+
+    pub fn move_nft_to_offer(&mut self) -> Result<()> {
+        let seeds = &[
+            b"auction",
+            self.producer.to_account_info().key.as_ref(),
+            &self.auction.id.to_le_bytes()[..],
+            &[self.auction.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        let accounts = TransferChecked {
+            from: self.auction_vault_token_account_a.to_account_info(),
+            mint: self.token_mint_a.to_account_info(),
+            to: self.vault_token_account_a.to_account_info(),
+            authority: self.auction.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        transfer_checked(cpi_context, 1, self.token_mint_a.decimals)?;
+
+        let accounts = CloseAccount {
+            account: self.auction_vault_token_account_a.to_account_info(),
+            destination: self.producer.to_account_info(),
+            authority: self.auction.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        close_account(cpi_context)
+    }
+
+    pub fn move_payment_to_offer(&mut self) -> Result<()> {
+        let amount = self.auction.highest_bid;
+
+        if self.auction.is_native() {
+            require!(
+                self.vault_token_account_b.is_none(),
+                GrpxProtocolError::InvalidPaymentAccounts
+            );
+
+            self.auction.sub_lamports(amount)?;
+            self.offer.add_lamports(amount)?;
+            return Ok(());
+        }
+
+        let (Some(auction_vault_token_account_b), Some(vault_token_account_b)) = (
+            &self.auction_vault_token_account_b,
+            &self.vault_token_account_b,
+        ) else {
+            return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+        };
+
+        let seeds = &[
+            b"auction",
+            self.producer.to_account_info().key.as_ref(),
+            &self.auction.id.to_le_bytes()[..],
+            &[self.auction.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        let accounts = TransferChecked {
+            from: auction_vault_token_account_b.to_account_info(),
+            mint: self.token_mint_b.to_account_info(),
+            to: vault_token_account_b.to_account_info(),
+            authority: self.auction.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        transfer_checked(cpi_context, amount, self.token_mint_b.decimals)?;
+
+        let accounts = CloseAccount {
+            account: auction_vault_token_account_b.to_account_info(),
+            destination: self.producer.to_account_info(),
+            authority: self.auction.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        close_account(cpi_context)
+    }
+}
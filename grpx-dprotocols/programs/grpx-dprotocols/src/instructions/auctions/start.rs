@@ -0,0 +1,115 @@
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::error::GrpxProtocolError;
+use crate::instructions::is_native_mint;
+use crate::state::Auction;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateAuction<'info> {
+    #[account(mut)]
+    pub producer: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = token_mint_a.supply == 1 && token_mint_a.decimals == 0 @ GrpxProtocolError::InvalidNftMint,
+    )]
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = producer,
+        associated_token::token_program = token_program
+    )]
+    pub producer_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = producer,
+        space = ANCHOR_DISCRIMINATOR + Auction::INIT_SPACE,
+        seeds = [b"auction", producer.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        init,
+        payer = producer,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = auction,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = producer,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = auction,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateAuction<'info> {
+    pub fn start_auction(
+        &mut self,
+        id: u64,
+        reserve_price: u64,
+        min_increment: u64,
+        ends_at: i64,
+        bumps: &CreateAuctionBumps,
+    ) -> Result<()> {
+        require!(
+            reserve_price > 0 && min_increment > 0,
+            GrpxProtocolError::InvalidBidAmount
+        );
+        require!(
+            ends_at > Clock::get()?.unix_timestamp,
+            GrpxProtocolError::InvalidExpiry
+        );
+        require!(
+            is_native_mint(&self.token_mint_b.key()) == self.vault_token_account_b.is_none(),
+            GrpxProtocolError::InvalidPaymentAccounts
+        );
+
+        self.auction.set_inner(Auction {
+            id,
+            producer: self.producer.key(),
+            token_mint_a: self.token_mint_a.key(),
+            token_mint_b: self.token_mint_b.key(),
+            reserve_price,
+            min_increment,
+            ends_at,
+            highest_bidder: None,
+            highest_bid: 0,
+            bump: bumps.auction,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_nft_to_vault(&mut self) -> Result<()> {
+        let transfer_accounts = TransferChecked {
+            from: self.producer_token_account_a.to_account_info(),
+            mint: self.token_mint_a.to_account_info(),
+            to: self.vault_token_account_a.to_account_info(),
+            authority: self.producer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+
+        transfer_checked(cpi_ctx, 1, self.token_mint_a.decimals)
+    }
+}
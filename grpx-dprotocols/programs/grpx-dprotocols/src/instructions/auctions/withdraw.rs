@@ -0,0 +1,122 @@
+use crate::{error::GrpxProtocolError, state::Auction};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct CancelAuction<'info> {
+    #[account(mut)]
+    pub producer: Signer<'info>,
+
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = producer,
+        associated_token::token_program = token_program,
+    )]
+    pub producer_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = producer,
+        has_one = producer,
+        has_one = token_mint_a,
+        has_one = token_mint_b,
+        constraint = auction.highest_bidder.is_none() @ GrpxProtocolError::AuctionHasBids,
+        seeds = [b"auction", producer.key().as_ref(), auction.id.to_le_bytes().as_ref()],
+        bump = auction.bump
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = auction,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = auction,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelAuction<'info> {
+    pub fn return_nft_to_producer(&mut self) -> Result<()> {
+        let seeds = &[
+            b"auction",
+            self.producer.to_account_info().key.as_ref(),
+            &self.auction.id.to_le_bytes()[..],
+            &[self.auction.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        let accounts = TransferChecked {
+            from: self.vault_token_account_a.to_account_info(),
+            mint: self.token_mint_a.to_account_info(),
+            to: self.producer_token_account_a.to_account_info(),
+            authority: self.auction.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        transfer_checked(
+            cpi_context,
+            self.vault_token_account_a.amount,
+            self.token_mint_a.decimals,
+        )?;
+
+        let accounts = CloseAccount {
+            account: self.vault_token_account_a.to_account_info(),
+            destination: self.producer.to_account_info(),
+            authority: self.auction.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        close_account(cpi_context)
+    }
+
+    pub fn close_payment_vault(&mut self) -> Result<()> {
+        let Some(vault_token_account_b) = &self.vault_token_account_b else {
+            return Ok(());
+        };
+
+        let seeds = &[
+            b"auction",
+            self.producer.to_account_info().key.as_ref(),
+            &self.auction.id.to_le_bytes()[..],
+            &[self.auction.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        let accounts = CloseAccount {
+            account: vault_token_account_b.to_account_info(),
+            destination: self.producer.to_account_info(),
+            authority: self.auction.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        close_account(cpi_context)
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    error::GrpxProtocolError,
+    instructions::{close_vault, transfer_from_vault},
+    CollectionBid,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CancelCollectionBid<'info> {
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    #[account(constraint = (
+        initiator.key() == bidder.key() ||
+        collection_bid.is_expired(Clock::get()?.unix_timestamp)
+    ) @ GrpxProtocolError::Unauthorized)]
+    pub initiator: Signer<'info>,
+
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        has_one = token_mint_b,
+        seeds = [b"collection_bid", bidder.key().as_ref(), collection_bid.id.to_le_bytes().as_ref()],
+        bump = collection_bid.bump
+    )]
+    pub collection_bid: Account<'info, CollectionBid>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = collection_bid,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelCollectionBid<'info> {
+    pub fn return_payment_to_bidder(&mut self) -> Result<()> {
+        // Lamport bids are returned to the bidder with the rent when the bid account closes.
+        if self.collection_bid.is_native() {
+            return Ok(());
+        }
+
+        let (Some(bidder_token_account_b), Some(vault_token_account_b)) =
+            (&self.bidder_token_account_b, &self.vault_token_account_b)
+        else {
+            return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+        };
+
+        let seeds = &[
+            b"collection_bid",
+            self.bidder.to_account_info().key.as_ref(),
+            &self.collection_bid.id.to_le_bytes()[..],
+            &[self.collection_bid.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        transfer_from_vault(
+            vault_token_account_b,
+            &self.token_mint_b,
+            bidder_token_account_b.to_account_info(),
+            self.collection_bid.to_account_info(),
+            vault_token_account_b.amount,
+            self.token_program.to_account_info(),
+            &signer_seeds,
+        )?;
+
+        close_vault(
+            vault_token_account_b.to_account_info(),
+            self.bidder.to_account_info(),
+            self.collection_bid.to_account_info(),
+            self.token_program.to_account_info(),
+            &signer_seeds,
+        )
+    }
+}
@@ -0,0 +1,191 @@
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::{
+    error::GrpxProtocolError,
+    instructions::escrowed_lamports,
+    state::{Bid, Offer, OfferStatus, ProducerCounter},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+#[derive(Accounts)]
+pub struct AcceptBidForDelivery<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = holder,
+        associated_token::token_program = token_program,
+    )]
+    pub holder_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        has_one = token_mint_a,
+        has_one = token_mint_b,
+        constraint = !bid.is_expired(Clock::get()?.unix_timestamp) @ GrpxProtocolError::BidExpired,
+        seeds = [b"bid", bidder.key().as_ref(), token_mint_a.key().as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = bid,
+        associated_token::token_program = token_program,
+    )]
+    pub bid_vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = holder,
+        space = ANCHOR_DISCRIMINATOR + ProducerCounter::INIT_SPACE,
+        seeds = [b"producer_counter", holder.key().as_ref()],
+        bump
+    )]
+    pub producer_counter: Account<'info, ProducerCounter>,
+
+    #[account(
+        init,
+        payer = holder,
+        space = ANCHOR_DISCRIMINATOR + Offer::INIT_SPACE,
+        seeds = [b"offer", holder.key().as_ref(), producer_counter.offer_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        init,
+        payer = holder,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = holder,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AcceptBidForDelivery<'info> {
+    pub fn open_accepted_offer(&mut self, bumps: &AcceptBidForDeliveryBumps) -> Result<()> {
+        let id = self
+            .producer_counter
+            .assign_offer_id(self.holder.key(), bumps.producer_counter)?;
+
+        self.offer.set_inner(Offer {
+            id,
+            producer: self.holder.key(),
+            consumer: Some(self.bidder.key()),
+            token_mint_a: self.token_mint_a.key(),
+            token_mint_b: self.token_mint_b.key(),
+            token_a_offered_amount: 1,
+            token_a_remaining_amount: 1,
+            token_a_accepted_amount: 1,
+            token_b_desired_amount: self.bid.token_b_offered_amount,
+            status: OfferStatus::Accepted,
+            bump: bumps.offer,
+            ..Default::default()
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_nft_to_vault(&mut self) -> Result<()> {
+        let transfer_accounts = TransferChecked {
+            from: self.holder_token_account_a.to_account_info(),
+            mint: self.token_mint_a.to_account_info(),
+            to: self.vault_token_account_a.to_account_info(),
+            authority: self.holder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+
+        transfer_checked(cpi_ctx, 1, self.token_mint_a.decimals)
+    }
+
+    pub fn move_payment_to_offer(&mut self) -> Result<()> {
+        if self.bid.is_native() {
+            require!(
+                self.vault_token_account_b.is_none(),
+                GrpxProtocolError::InvalidPaymentAccounts
+            );
+
+            let amount = escrowed_lamports(&self.bid.to_account_info())?;
+            self.bid.sub_lamports(amount)?;
+            self.offer.add_lamports(amount)?;
+            return Ok(());
+        }
+
+        let (Some(bid_vault_token_account_b), Some(vault_token_account_b)) =
+            (&self.bid_vault_token_account_b, &self.vault_token_account_b)
+        else {
+            return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+        };
+
+        let seeds = &[
+            b"bid",
+            self.bidder.to_account_info().key.as_ref(),
+            self.bid.token_mint_a.as_ref(),
+            &[self.bid.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        let accounts = TransferChecked {
+            from: bid_vault_token_account_b.to_account_info(),
+            mint: self.token_mint_b.to_account_info(),
+            to: vault_token_account_b.to_account_info(),
+            authority: self.bid.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        transfer_checked(
+            cpi_context,
+            bid_vault_token_account_b.amount,
+            self.token_mint_b.decimals,
+        )?;
+
+        let accounts = CloseAccount {
+            account: bid_vault_token_account_b.to_account_info(),
+            destination: self.bidder.to_account_info(),
+            authority: self.bid.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        close_account(cpi_context)
+    }
+}
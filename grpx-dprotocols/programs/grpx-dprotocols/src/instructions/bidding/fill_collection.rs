@@ -0,0 +1,198 @@
+use crate::{
+    error::GrpxProtocolError,
+    instructions::{close_vault, pay_creator_royalties, pay_from_escrow},
+    state::{CollectionBid, WineAttributes},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[derive(Accounts)]
+pub struct FillCollectionBid<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = holder,
+        associated_token::token_program = token_program,
+    )]
+    pub holder_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = holder,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = holder,
+        associated_token::token_program = token_program,
+    )]
+    pub holder_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = holder,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = bidder,
+        has_one = token_mint_b,
+        constraint = !collection_bid.is_expired(Clock::get()?.unix_timestamp) @ GrpxProtocolError::BidExpired,
+        seeds = [b"collection_bid", bidder.key().as_ref(), collection_bid.id.to_le_bytes().as_ref()],
+        bump = collection_bid.bump
+    )]
+    pub collection_bid: Account<'info, CollectionBid>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = collection_bid,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), token_mint_a.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+        constraint = metadata_a.collection.as_ref().is_some_and(|collection| {
+            collection.verified && collection.key == collection_bid.collection_mint
+        }) @ GrpxProtocolError::NotInCollection,
+    )]
+    pub metadata_a: Account<'info, MetadataAccount>,
+
+    #[account(
+        seeds = [b"attributes", token_mint_a.key().as_ref()],
+        bump = wine_attributes.bump,
+    )]
+    pub wine_attributes: Option<Account<'info, WineAttributes>>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FillCollectionBid<'info> {
+    pub fn check_required_attribute(&self) -> Result<()> {
+        let Some(required_attribute) = &self.collection_bid.required_attribute else {
+            return Ok(());
+        };
+
+        require!(
+            self.wine_attributes
+                .as_ref()
+                .is_some_and(|wine_attributes| wine_attributes.contains(required_attribute)),
+            GrpxProtocolError::AttributeMismatch
+        );
+
+        Ok(())
+    }
+
+    pub fn pay_holder(&mut self, creator_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        let price = self.collection_bid.token_b_offered_amount;
+        let payment_mint = (!self.collection_bid.is_native()).then(|| self.token_mint_b.key());
+
+        let royalties = pay_creator_royalties(
+            &self.metadata_a.to_account_info(),
+            price,
+            payment_mint,
+            creator_accounts,
+            |creator_account, amount| self.pay_from_escrow(creator_account.clone(), amount),
+        )?;
+
+        let holder_account = if self.collection_bid.is_native() {
+            self.holder.to_account_info()
+        } else {
+            self.holder_token_account_b
+                .as_ref()
+                .ok_or(GrpxProtocolError::InvalidPaymentAccounts)?
+                .to_account_info()
+        };
+        let amount = price
+            .checked_sub(royalties)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
+
+        self.pay_from_escrow(holder_account, amount)
+    }
+
+    pub fn transfer_nft_to_bidder(&mut self) -> Result<()> {
+        let accounts = TransferChecked {
+            from: self.holder_token_account_a.to_account_info(),
+            mint: self.token_mint_a.to_account_info(),
+            to: self.bidder_token_account_a.to_account_info(),
+            authority: self.holder.to_account_info(),
+        };
+        let cpi_context = CpiContext::new(self.token_program.to_account_info(), accounts);
+        transfer_checked(cpi_context, 1, self.token_mint_a.decimals)?;
+
+        self.collection_bid.quantity_remaining = self
+            .collection_bid
+            .quantity_remaining
+            .checked_sub(1)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn close_if_filled(&mut self) -> Result<()> {
+        if self.collection_bid.quantity_remaining > 0 {
+            return Ok(());
+        }
+
+        if let Some(vault_token_account_b) = &self.vault_token_account_b {
+            let seeds = &[
+                b"collection_bid",
+                self.bidder.to_account_info().key.as_ref(),
+                &self.collection_bid.id.to_le_bytes()[..],
+                &[self.collection_bid.bump],
+            ];
+            let signer_seeds = [&seeds[..]];
+
+            close_vault(
+                vault_token_account_b.to_account_info(),
+                self.bidder.to_account_info(),
+                self.collection_bid.to_account_info(),
+                self.token_program.to_account_info(),
+                &signer_seeds,
+            )?;
+        }
+
+        self.collection_bid.close(self.bidder.to_account_info())
+    }
+
+    fn pay_from_escrow(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let seeds = &[
+            b"collection_bid",
+            self.bidder.to_account_info().key.as_ref(),
+            &self.collection_bid.id.to_le_bytes()[..],
+            &[self.collection_bid.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        pay_from_escrow(
+            self.collection_bid.to_account_info(),
+            self.vault_token_account_b.as_ref(),
+            &self.token_mint_b,
+            to,
+            amount,
+            self.token_program.to_account_info(),
+            &signer_seeds,
+        )
+    }
+}
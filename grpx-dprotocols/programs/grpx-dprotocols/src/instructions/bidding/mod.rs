@@ -0,0 +1,15 @@
+pub mod cancel;
+pub mod cancel_collection;
+pub mod deliver;
+pub mod fill_collection;
+pub mod place;
+pub mod place_collection;
+pub mod sell;
+
+pub use cancel::*;
+pub use cancel_collection::*;
+pub use deliver::*;
+pub use fill_collection::*;
+pub use place::*;
+pub use place_collection::*;
+pub use sell::*;
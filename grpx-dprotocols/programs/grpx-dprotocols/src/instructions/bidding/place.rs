@@ -0,0 +1,118 @@
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::error::GrpxProtocolError;
+use crate::state::Bid;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = token_mint_a.supply == 1 && token_mint_a.decimals == 0 @ GrpxProtocolError::InvalidNftMint,
+    )]
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = ANCHOR_DISCRIMINATOR + Bid::INIT_SPACE,
+        seeds = [b"bid", bidder.key().as_ref(), token_mint_a.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        init,
+        payer = bidder,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = bid,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceBid<'info> {
+    pub fn place_bid(
+        &mut self,
+        token_b_offered_amount: u64,
+        expires_at: i64,
+        bumps: &PlaceBidBumps,
+    ) -> Result<()> {
+        require!(
+            token_b_offered_amount > 0,
+            GrpxProtocolError::InvalidBidAmount
+        );
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            GrpxProtocolError::InvalidExpiry
+        );
+
+        self.bid.set_inner(Bid {
+            bidder: self.bidder.key(),
+            token_mint_a: self.token_mint_a.key(),
+            token_mint_b: self.token_mint_b.key(),
+            token_b_offered_amount,
+            expires_at,
+            bump: bumps.bid,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_payment(&mut self) -> Result<()> {
+        if self.bid.is_native() {
+            require!(
+                self.vault_token_account_b.is_none(),
+                GrpxProtocolError::InvalidPaymentAccounts
+            );
+
+            let transfer_accounts = Transfer {
+                from: self.bidder.to_account_info(),
+                to: self.bid.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), transfer_accounts);
+            return transfer(cpi_ctx, self.bid.token_b_offered_amount);
+        }
+
+        let (Some(bidder_token_account_b), Some(vault_token_account_b)) =
+            (&self.bidder_token_account_b, &self.vault_token_account_b)
+        else {
+            return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+        };
+
+        let transfer_accounts = TransferChecked {
+            from: bidder_token_account_b.to_account_info(),
+            mint: self.token_mint_b.to_account_info(),
+            to: vault_token_account_b.to_account_info(),
+            authority: self.bidder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+        transfer_checked(
+            cpi_ctx,
+            self.bid.token_b_offered_amount,
+            self.token_mint_b.decimals,
+        )
+    }
+}
@@ -0,0 +1,131 @@
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::error::GrpxProtocolError;
+use crate::state::{CollectionBid, WineAttribute};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::Mint as CollectionMint,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct PlaceCollectionBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    pub collection_mint: Account<'info, CollectionMint>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = ANCHOR_DISCRIMINATOR + CollectionBid::INIT_SPACE,
+        seeds = [b"collection_bid", bidder.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub collection_bid: Account<'info, CollectionBid>,
+
+    #[account(
+        init,
+        payer = bidder,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = collection_bid,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceCollectionBid<'info> {
+    pub fn place_collection_bid(
+        &mut self,
+        id: u64,
+        token_b_offered_amount: u64,
+        quantity: u64,
+        required_attribute: Option<WineAttribute>,
+        expires_at: i64,
+        bumps: &PlaceCollectionBidBumps,
+    ) -> Result<()> {
+        require!(
+            token_b_offered_amount > 0,
+            GrpxProtocolError::InvalidBidAmount
+        );
+        require!(quantity > 0, GrpxProtocolError::InvalidQuantity);
+        if let Some(required_attribute) = &required_attribute {
+            require!(
+                required_attribute.is_valid(),
+                GrpxProtocolError::InvalidMetadata
+            );
+        }
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            GrpxProtocolError::InvalidExpiry
+        );
+
+        self.collection_bid.set_inner(CollectionBid {
+            id,
+            bidder: self.bidder.key(),
+            collection_mint: self.collection_mint.key(),
+            token_mint_b: self.token_mint_b.key(),
+            token_b_offered_amount,
+            quantity_remaining: quantity,
+            required_attribute,
+            expires_at,
+            bump: bumps.collection_bid,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_payment(&mut self) -> Result<()> {
+        let amount = self
+            .collection_bid
+            .token_b_offered_amount
+            .checked_mul(self.collection_bid.quantity_remaining)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
+
+        if self.collection_bid.is_native() {
+            require!(
+                self.vault_token_account_b.is_none(),
+                GrpxProtocolError::InvalidPaymentAccounts
+            );
+
+            let transfer_accounts = Transfer {
+                from: self.bidder.to_account_info(),
+                to: self.collection_bid.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), transfer_accounts);
+            return transfer(cpi_ctx, amount);
+        }
+
+        let (Some(bidder_token_account_b), Some(vault_token_account_b)) =
+            (&self.bidder_token_account_b, &self.vault_token_account_b)
+        else {
+            return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+        };
+
+        let transfer_accounts = TransferChecked {
+            from: bidder_token_account_b.to_account_info(),
+            mint: self.token_mint_b.to_account_info(),
+            to: vault_token_account_b.to_account_info(),
+            authority: self.bidder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+        transfer_checked(cpi_ctx, amount, self.token_mint_b.decimals)
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    error::GrpxProtocolError,
+    instructions::{close_vault, escrowed_payment, pay_creator_royalties, pay_from_escrow},
+    Bid,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::Metadata,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[derive(Accounts)]
+pub struct AcceptBid<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = holder,
+        associated_token::token_program = token_program,
+    )]
+    pub holder_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = holder,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = holder,
+        associated_token::token_program = token_program,
+    )]
+    pub holder_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = holder,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        has_one = token_mint_a,
+        has_one = token_mint_b,
+        constraint = !bid.is_expired(Clock::get()?.unix_timestamp) @ GrpxProtocolError::BidExpired,
+        seeds = [b"bid", bidder.key().as_ref(), token_mint_a.key().as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = bid,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), token_mint_a.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    /// CHECK: Metaplex metadata PDA of token_mint_a, only deserialized when it has been initialized
+    pub metadata_a: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AcceptBid<'info> {
+    pub fn pay_creator_royalties(
+        &mut self,
+        creator_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let proceeds = self.escrowed_payment()?;
+        let payment_mint = (!self.bid.is_native()).then(|| self.token_mint_b.key());
+
+        pay_creator_royalties(
+            &self.metadata_a,
+            proceeds,
+            payment_mint,
+            creator_accounts,
+            |creator_account, amount| self.pay_from_escrow(creator_account.clone(), amount),
+        )?;
+
+        if let Some(vault_token_account_b) = self.vault_token_account_b.as_mut() {
+            vault_token_account_b.reload()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn transfer_payment_to_holder(&mut self) -> Result<()> {
+        let holder_account = if self.bid.is_native() {
+            self.holder.to_account_info()
+        } else {
+            self.holder_token_account_b
+                .as_ref()
+                .ok_or(GrpxProtocolError::InvalidPaymentAccounts)?
+                .to_account_info()
+        };
+        let amount = self.escrowed_payment()?;
+
+        self.pay_from_escrow(holder_account, amount)
+    }
+
+    pub fn transfer_nft_to_bidder(&mut self) -> Result<()> {
+        let accounts = TransferChecked {
+            from: self.holder_token_account_a.to_account_info(),
+            mint: self.token_mint_a.to_account_info(),
+            to: self.bidder_token_account_a.to_account_info(),
+            authority: self.holder.to_account_info(),
+        };
+        let cpi_context = CpiContext::new(self.token_program.to_account_info(), accounts);
+
+        transfer_checked(cpi_context, 1, self.token_mint_a.decimals)
+    }
+
+    pub fn close_vault(&mut self) -> Result<()> {
+        let Some(vault_token_account_b) = &self.vault_token_account_b else {
+            return Ok(());
+        };
+
+        let seeds = &[
+            b"bid",
+            self.bidder.to_account_info().key.as_ref(),
+            self.bid.token_mint_a.as_ref(),
+            &[self.bid.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        close_vault(
+            vault_token_account_b.to_account_info(),
+            self.bidder.to_account_info(),
+            self.bid.to_account_info(),
+            self.token_program.to_account_info(),
+            &signer_seeds,
+        )
+    }
+
+    fn escrowed_payment(&self) -> Result<u64> {
+        escrowed_payment(
+            &self.bid.to_account_info(),
+            self.vault_token_account_b.as_ref(),
+            &self.token_mint_b.key(),
+        )
+    }
+
+    fn pay_from_escrow(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let seeds = &[
+            b"bid",
+            self.bidder.to_account_info().key.as_ref(),
+            self.bid.token_mint_a.as_ref(),
+            &[self.bid.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        pay_from_escrow(
+            self.bid.to_account_info(),
+            self.vault_token_account_b.as_ref(),
+            &self.token_mint_b,
+            to,
+            amount,
+            self.token_program.to_account_info(),
+            &signer_seeds,
+        )
+    }
+}
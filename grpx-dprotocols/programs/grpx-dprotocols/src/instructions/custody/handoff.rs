@@ -0,0 +1,39 @@
+use crate::constants::MAX_CUSTODY_HOPS;
+use crate::error::GrpxProtocolError;
+use crate::state::{CustodyChain, CustodyHop};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Handoff<'info> {
+    pub from: Signer<'info>,
+
+    #[account(constraint = to.key() != from.key() @ GrpxProtocolError::InvalidCustodian)]
+    pub to: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = custody_chain.custodian == from.key() @ GrpxProtocolError::InvalidCustodian,
+        seeds = [b"custody", custody_chain.mint.as_ref()],
+        bump = custody_chain.bump
+    )]
+    pub custody_chain: Account<'info, CustodyChain>,
+}
+
+impl<'info> Handoff<'info> {
+    pub fn record_handoff(&mut self, location_hash: Option<[u8; 32]>) -> Result<()> {
+        require!(
+            self.custody_chain.hops.len() < MAX_CUSTODY_HOPS,
+            GrpxProtocolError::CustodyChainFull
+        );
+
+        self.custody_chain.hops.push(CustodyHop {
+            from: self.from.key(),
+            to: self.to.key(),
+            location_hash,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        self.custody_chain.custodian = self.to.key();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub mod handoff;
+pub mod register;
+
+pub use handoff::*;
+pub use register::*;
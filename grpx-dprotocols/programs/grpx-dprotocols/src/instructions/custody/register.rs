@@ -0,0 +1,47 @@
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::error::GrpxProtocolError;
+use crate::state::CustodyChain;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct RegisterCustody<'info> {
+    #[account(mut)]
+    pub custodian: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        associated_token::mint = token_mint_a,
+        associated_token::authority = custodian,
+        associated_token::token_program = token_program,
+        constraint = custodian_token_account_a.amount > 0 @ GrpxProtocolError::Unauthorized,
+    )]
+    pub custodian_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = custodian,
+        space = ANCHOR_DISCRIMINATOR + CustodyChain::INIT_SPACE,
+        seeds = [b"custody", token_mint_a.key().as_ref()],
+        bump
+    )]
+    pub custody_chain: Account<'info, CustodyChain>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterCustody<'info> {
+    pub fn register(&mut self, bumps: &RegisterCustodyBumps) -> Result<()> {
+        self.custody_chain.set_inner(CustodyChain {
+            mint: self.token_mint_a.key(),
+            custodian: self.custodian.key(),
+            hops: Vec::new(),
+            bump: bumps.custody_chain,
+        });
+
+        Ok(())
+    }
+}
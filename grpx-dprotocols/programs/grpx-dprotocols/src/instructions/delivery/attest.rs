@@ -0,0 +1,155 @@
+use crate::{
+    error::GrpxProtocolError,
+    instructions::{
+        escrowed_payment, pay_creator_royalties, pay_from_escrow, verify_ed25519_signature,
+    },
+    Offer, OfferStatus,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::Metadata,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+pub struct AttestDelivery<'info> {
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+
+    #[account(mut)]
+    pub producer: SystemAccount<'info>,
+
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = submitter,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = producer,
+        associated_token::token_program = token_program,
+    )]
+    pub producer_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = producer,
+        has_one = token_mint_a,
+        has_one = token_mint_b,
+        constraint = offer.attestor.is_some() @ GrpxProtocolError::InvalidAttestation,
+        constraint = !offer.delivery_attested @ GrpxProtocolError::InvalidOfferStatus,
+        constraint = offer.is_location_cleared() @ GrpxProtocolError::LocationNotVerified,
+        constraint = (
+            offer.is_awaiting_confirmation() &&
+            offer.status != OfferStatus::Disputed
+        ) @ GrpxProtocolError::InvalidOfferStatus,
+        seeds = [b"offer", offer.producer.as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), token_mint_a.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    /// CHECK: Metaplex metadata PDA of token_mint_a, only deserialized when it has been initialized
+    pub metadata_a: UncheckedAccount<'info>,
+
+    #[account(address = solana_instructions_sysvar::ID)]
+    /// CHECK: the instructions sysvar, checked by address
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AttestDelivery<'info> {
+    pub fn record_delivery(&mut self) -> Result<()> {
+        let attestor = self
+            .offer
+            .attestor
+            .ok_or(GrpxProtocolError::InvalidAttestation)?;
+        let message = self.offer.delivery_message(&self.offer.key());
+
+        verify_ed25519_signature(&self.instructions_sysvar, &attestor, &message)?;
+
+        self.offer.delivered_at = Some(Clock::get()?.unix_timestamp);
+        self.offer.delivery_attested = true;
+        self.offer.status = OfferStatus::Delivered;
+
+        Ok(())
+    }
+
+    /// Held back until the attestor proves delivery, so a shipment that never arrives is
+    /// refunded in full.
+    pub fn release_shipment_share(
+        &mut self,
+        creator_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let escrowed = self.escrowed_payment()?;
+        let release = (escrowed as u128 * self.offer.shipment_release_bps as u128 / 10_000) as u64;
+        if release == 0 {
+            return Ok(());
+        }
+
+        let payment_mint = (!self.offer.is_native()).then(|| self.token_mint_b.key());
+        let royalties = pay_creator_royalties(
+            &self.metadata_a,
+            release,
+            payment_mint,
+            creator_accounts,
+            |creator_account, amount| self.pay_from_escrow(creator_account.clone(), amount),
+        )?;
+
+        let producer_account = if self.offer.is_native() {
+            self.producer.to_account_info()
+        } else {
+            self.producer_token_account_b
+                .as_ref()
+                .ok_or(GrpxProtocolError::InvalidPaymentAccounts)?
+                .to_account_info()
+        };
+        let amount = release
+            .checked_sub(royalties)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
+
+        self.pay_from_escrow(producer_account, amount)
+    }
+
+    fn escrowed_payment(&self) -> Result<u64> {
+        escrowed_payment(
+            &self.offer.to_account_info(),
+            self.vault_token_account_b.as_ref(),
+            &self.token_mint_b.key(),
+        )
+    }
+
+    fn pay_from_escrow(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
+        let signer_seeds = [&seeds[..]];
+
+        pay_from_escrow(
+            self.offer.to_account_info(),
+            self.vault_token_account_b.as_ref(),
+            &self.token_mint_b,
+            to,
+            amount,
+            self.token_program.to_account_info(),
+            &signer_seeds,
+        )
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{error::GrpxProtocolError, instructions::verify_ed25519_signature, Offer};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct BreachReport {
+    pub min_recorded: i16,
+    pub max_recorded: i16,
+    pub excursions: u16,
+}
+
+#[derive(Accounts)]
+pub struct ReportBreach<'info> {
+    pub submitter: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = offer.cold_chain.is_some() @ GrpxProtocolError::InvalidColdChainTerms,
+        constraint = offer.is_awaiting_confirmation() @ GrpxProtocolError::InvalidOfferStatus,
+        seeds = [b"offer", offer.producer.as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(address = solana_instructions_sysvar::ID)]
+    /// CHECK: the instructions sysvar, checked by address
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+impl<'info> ReportBreach<'info> {
+    pub fn record_breach(&mut self, report: BreachReport) -> Result<()> {
+        let cold_chain = self
+            .offer
+            .cold_chain
+            .ok_or(GrpxProtocolError::InvalidColdChainTerms)?;
+        let message = self.offer.breach_message(
+            &self.offer.key(),
+            report.min_recorded,
+            report.max_recorded,
+            report.excursions,
+        );
+
+        verify_ed25519_signature(&self.instructions_sysvar, &cold_chain.sensor, &message)?;
+
+        // Reports only ever raise the penalty, so replaying an older one changes nothing.
+        let penalty_bps =
+            cold_chain.penalty_bps(report.min_recorded, report.max_recorded, report.excursions);
+        self.offer.breach_penalty_bps = self.offer.breach_penalty_bps.max(penalty_bps);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{error::GrpxProtocolError, Offer, OfferStatus};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct DisputeDelivery<'info> {
+    pub consumer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = offer.consumer == Some(consumer.key()) @ GrpxProtocolError::Unauthorized,
+        constraint = offer.is_disputable(Clock::get()?.unix_timestamp) @ GrpxProtocolError::DisputeWindowClosed,
+        seeds = [b"offer", offer.producer.as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+}
+
+impl<'info> DisputeDelivery<'info> {
+    pub fn open_dispute(&mut self) -> Result<()> {
+        self.offer.status = OfferStatus::Disputed;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{error::GrpxProtocolError, Offer, OfferStatus};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RevealDeliveryCode<'info> {
+    #[account(
+        constraint = (
+            authority.key() == offer.producer ||
+            authority.key() == offer.delivery_authority()
+        ) @ GrpxProtocolError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = offer.delivery_code_hash.is_some() @ GrpxProtocolError::InvalidDeliveryCode,
+        constraint = !offer.delivery_code_revealed @ GrpxProtocolError::InvalidOfferStatus,
+        constraint = !offer.is_delivery_code_expired(Clock::get()?.unix_timestamp) @ GrpxProtocolError::DeliveryCodeExpired,
+        constraint = matches!(
+            offer.status,
+            OfferStatus::Accepted | OfferStatus::Shipped | OfferStatus::InTransit | OfferStatus::Delivered
+        ) @ GrpxProtocolError::InvalidOfferStatus,
+        seeds = [b"offer", offer.producer.as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+}
+
+impl<'info> RevealDeliveryCode<'info> {
+    pub fn record_handover(&mut self, code: &[u8]) -> Result<()> {
+        require!(
+            self.offer.matches_delivery_code(code),
+            GrpxProtocolError::InvalidDeliveryCode
+        );
+
+        self.offer.delivery_code_revealed = true;
+        self.offer.delivered_at = Some(Clock::get()?.unix_timestamp);
+        self.offer.status = OfferStatus::Delivered;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{error::GrpxProtocolError, instructions::verify_ed25519_signature, Offer};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct VerifyLocation<'info> {
+    pub submitter: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = offer.geofence.is_some() @ GrpxProtocolError::InvalidGeofence,
+        constraint = offer.is_awaiting_confirmation() @ GrpxProtocolError::InvalidOfferStatus,
+        seeds = [b"offer", offer.producer.as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(address = solana_instructions_sysvar::ID)]
+    /// CHECK: the instructions sysvar, checked by address
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+impl<'info> VerifyLocation<'info> {
+    pub fn record_location(&mut self, geohash: &str) -> Result<()> {
+        let geofence = self
+            .offer
+            .geofence
+            .as_ref()
+            .ok_or(GrpxProtocolError::InvalidGeofence)?;
+        let message = self.offer.location_message(&self.offer.key(), geohash);
+
+        verify_ed25519_signature(&self.instructions_sysvar, &geofence.beacon, &message)?;
+        require!(
+            geofence.contains(geohash),
+            GrpxProtocolError::OutsideGeofence
+        );
+
+        self.offer.location_verified = true;
+
+        Ok(())
+    }
+}
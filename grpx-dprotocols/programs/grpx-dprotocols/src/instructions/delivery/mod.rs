@@ -0,0 +1,15 @@
+pub mod attest;
+pub mod breach;
+pub mod dispute;
+pub mod handover;
+pub mod locate;
+pub mod ship;
+pub mod track;
+
+pub use attest::*;
+pub use breach::*;
+pub use dispute::*;
+pub use handover::*;
+pub use locate::*;
+pub use ship::*;
+pub use track::*;
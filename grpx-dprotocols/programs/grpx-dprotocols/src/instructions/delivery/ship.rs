@@ -0,0 +1,31 @@
+use crate::{error::GrpxProtocolError, Offer, OfferStatus};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ShipOffer<'info> {
+    pub producer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = producer,
+        constraint = offer.status == OfferStatus::Accepted @ GrpxProtocolError::InvalidOfferStatus,
+        seeds = [b"offer", producer.key().as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+}
+
+impl<'info> ShipOffer<'info> {
+    pub fn mark_shipped(
+        &mut self,
+        tracking_hash: Option<[u8; 32]>,
+        carrier: Option<Pubkey>,
+    ) -> Result<()> {
+        self.offer.tracking_hash = tracking_hash;
+        self.offer.carrier = carrier;
+        self.offer.shipped_at = Some(Clock::get()?.unix_timestamp);
+        self.offer.status = OfferStatus::Shipped;
+
+        Ok(())
+    }
+}
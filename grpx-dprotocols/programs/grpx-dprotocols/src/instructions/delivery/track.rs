@@ -0,0 +1,47 @@
+use crate::{error::GrpxProtocolError, Offer, OfferStatus};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryMilestone {
+    InTransit,
+    Delivered,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDelivery<'info> {
+    #[account(constraint = authority.key() == offer.delivery_authority() @ GrpxProtocolError::Unauthorized)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"offer", offer.producer.as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+}
+
+impl<'info> UpdateDelivery<'info> {
+    pub fn advance(
+        &mut self,
+        milestone: DeliveryMilestone,
+        tracking_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        let status = match (self.offer.status, milestone) {
+            (OfferStatus::Shipped, DeliveryMilestone::InTransit) => OfferStatus::InTransit,
+            (OfferStatus::Shipped | OfferStatus::InTransit, DeliveryMilestone::Delivered) => {
+                OfferStatus::Delivered
+            }
+            _ => return Err(GrpxProtocolError::InvalidMilestone.into()),
+        };
+
+        if tracking_hash.is_some() {
+            self.offer.tracking_hash = tracking_hash;
+        }
+        if status == OfferStatus::Delivered {
+            self.offer.delivered_at = Some(Clock::get()?.unix_timestamp);
+        }
+        self.offer.status = status;
+
+        Ok(())
+    }
+}
@@ -1,10 +1,24 @@
-use crate::{error::GrpxProtocolError, Offer, OfferStatus};
+use crate::constants::{ANCHOR_DISCRIMINATOR, DELIVERY_CODE_TTL_SECONDS, MAX_SHIPPING_DETAILS_LEN};
+use crate::{
+    error::GrpxProtocolError, instructions::BundleItem, Offer, OfferAccepted, OfferStatus,
+    ShippingDetails,
+};
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
+
+#[event_cpi]
 #[derive(Accounts)]
+#[instruction(
+    quantity: u64,
+    proof: Vec<[u8; 32]>,
+    expected_version: u32,
+    delivery_code_hash: Option<[u8; 32]>,
+    shipping_details: Option<ShippingDetails>,
+)]
 pub struct AcceptOffer<'info> {
     #[account(mut)]
     pub producer: SystemAccount<'info>,
@@ -22,7 +36,7 @@ pub struct AcceptOffer<'info> {
         associated_token::authority = consumer,
         associated_token::token_program = token_program,
     )]
-    pub consumer_token_account_b: InterfaceAccount<'info, TokenAccount>,
+    pub consumer_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
@@ -30,10 +44,16 @@ pub struct AcceptOffer<'info> {
         has_one = token_mint_a,
         has_one = token_mint_b,
         constraint = offer.status == OfferStatus::Created @ GrpxProtocolError::InvalidOfferStatus,
+        constraint = !offer.is_expired(Clock::get()?.unix_timestamp) @ GrpxProtocolError::OfferExpired,
+        realloc = ANCHOR_DISCRIMINATOR
+            + Offer::INIT_SPACE
+            + shipping_details.as_ref().map_or(0, |details| details.ciphertext.len()),
+        realloc::payer = consumer,
+        realloc::zero = false,
         seeds = [b"offer", producer.key().as_ref(), offer.id.to_le_bytes().as_ref()],
         bump = offer.bump
     )]
-    offer: Account<'info, Offer>,
+    pub offer: Account<'info, Offer>,
 
     #[account(
         init_if_needed,
@@ -42,7 +62,7 @@ pub struct AcceptOffer<'info> {
         associated_token::authority = offer,
         associated_token::token_program = token_program,
     )]
-    pub vault_token_account_b: InterfaceAccount<'info, TokenAccount>,
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -50,25 +70,121 @@ pub struct AcceptOffer<'info> {
 }
 
 impl<'info> AcceptOffer<'info> {
-    pub fn deposit_sol_to_vault(&mut self) -> Result<()> {
+    pub fn set_delivery_code(&mut self, delivery_code_hash: Option<[u8; 32]>) -> Result<()> {
+        self.offer.delivery_code_hash = delivery_code_hash;
+        let now = Clock::get()?.unix_timestamp;
+        self.offer.delivery_code_expires_at =
+            delivery_code_hash.map(|_| now + DELIVERY_CODE_TTL_SECONDS);
+
+        Ok(())
+    }
+
+    pub fn attach_shipping_details(
+        &mut self,
+        shipping_details: Option<ShippingDetails>,
+    ) -> Result<()> {
+        if let Some(details) = &shipping_details {
+            require!(
+                details.ciphertext.len() <= MAX_SHIPPING_DETAILS_LEN,
+                GrpxProtocolError::ShippingDetailsTooLarge
+            );
+        }
+
+        self.offer.shipping_details = shipping_details;
+
+        Ok(())
+    }
+
+    pub fn deposit_payment(&mut self, quantity: u64) -> Result<()> {
+        self.offer.check_quantity(quantity)?;
+
+        let amount = self.offer.price_for(quantity)?;
+        if self.offer.is_native() {
+            self.deposit_sol_to_offer(amount)?;
+        } else {
+            self.deposit_tokens_to_vault(amount)?;
+        }
+
+        self.offer.token_a_accepted_amount = quantity;
+        self.offer.consumer = Some(self.consumer.key());
+        self.offer.status = OfferStatus::Accepted;
+        Ok(())
+    }
+
+    pub fn deposit_barter(&mut self, barter_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        let Some(terms) = &self.offer.barter else {
+            return Ok(());
+        };
+        require!(
+            self.offer.token_a_accepted_amount == self.offer.token_a_remaining_amount,
+            GrpxProtocolError::InvalidQuantity
+        );
+
+        let token_program = self.token_program.key();
+        let items = BundleItem::parse_barter(barter_accounts, terms, &token_program)?;
+
+        for item in &items {
+            item.validate(&self.offer.key(), &self.consumer.key(), &token_program)?;
+            item.deposit(
+                &self.consumer.to_account_info(),
+                &self.offer.to_account_info(),
+                &self.token_program.to_account_info(),
+                &self.associated_token_program.to_account_info(),
+                &self.system_program.to_account_info(),
+            )?;
+        }
+
+        self.offer.barter_mints = items.iter().map(|item| item.mint.key()).collect();
+
+        Ok(())
+    }
+
+    pub fn accepted_event(&self) -> Result<OfferAccepted> {
+        Ok(OfferAccepted {
+            offer: self.offer.key(),
+            producer: self.producer.key(),
+            consumer: self.consumer.key(),
+            token_mint_a: self.token_mint_a.key(),
+            token_mint_b: self.token_mint_b.key(),
+            quantity: self.offer.token_a_accepted_amount,
+            token_b_amount: self.offer.price_for(self.offer.token_a_accepted_amount)?,
+            version: self.offer.version,
+            status: self.offer.status,
+        })
+    }
+
+    pub fn deposit_sol_to_offer(&mut self, amount: u64) -> Result<()> {
+        require!(
+            self.vault_token_account_b.is_none(),
+            GrpxProtocolError::InvalidPaymentAccounts
+        );
+
+        let transfer_accounts = Transfer {
+            from: self.consumer.to_account_info(),
+            to: self.offer.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), transfer_accounts);
+        transfer(cpi_ctx, amount)
+    }
+
+    pub fn deposit_tokens_to_vault(&mut self, amount: u64) -> Result<()> {
+        let (Some(consumer_token_account_b), Some(vault_token_account_b)) =
+            (&self.consumer_token_account_b, &self.vault_token_account_b)
+        else {
+            return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+        };
+
         let cpi_program = self.token_program.to_account_info();
 
         let transfer_accounts = TransferChecked {
-            from: self.consumer_token_account_b.to_account_info(),
+            from: consumer_token_account_b.to_account_info(),
             mint: self.token_mint_b.to_account_info(),
-            to: self.vault_token_account_b.to_account_info(),
+            to: vault_token_account_b.to_account_info(),
             authority: self.consumer.to_account_info(),
         };
 
         let cpi_ctx = CpiContext::new(cpi_program, transfer_accounts);
-        transfer_checked(
-            cpi_ctx,
-            self.offer.token_b_desired_amount,
-            self.token_mint_b.decimals,
-        )?;
-
-        self.offer.consumer = Some(self.consumer.key());
-        self.offer.status = OfferStatus::Accepted;
-        Ok(())
+        transfer_checked(cpi_ctx, amount, self.token_mint_b.decimals)
     }
 }
@@ -0,0 +1,120 @@
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::{
+    error::GrpxProtocolError, BarterTerms, ColdChainTerms, Geofence, Listing, Offer,
+    OfferAllowlist, OfferStatus,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AmendArgs {
+    pub token_b_desired_amount: u64,
+    pub allowlist: Option<OfferAllowlist>,
+    pub expires_at: Option<i64>,
+    pub shipment_release_bps: u16,
+    pub attestor: Option<Pubkey>,
+    pub cold_chain: Option<ColdChainTerms>,
+    pub requires_custody: bool,
+    pub geofence: Option<Geofence>,
+    pub barter: Option<BarterTerms>,
+}
+
+#[derive(Accounts)]
+pub struct AmendOffer<'info> {
+    #[account(mut)]
+    pub producer: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = producer,
+        constraint = offer.status == OfferStatus::Created @ GrpxProtocolError::InvalidOfferStatus,
+        seeds = [b"offer", producer.key().as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        init_if_needed,
+        payer = producer,
+        space = ANCHOR_DISCRIMINATOR + Listing::INIT_SPACE,
+        seeds = [b"listing", offer.token_mint_a.as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AmendOffer<'info> {
+    pub fn amend_terms(&mut self, amend_args: AmendArgs, bumps: &AmendOfferBumps) -> Result<()> {
+        if let Some(expires_at) = amend_args.expires_at {
+            require!(
+                expires_at > Clock::get()?.unix_timestamp,
+                GrpxProtocolError::InvalidExpiry
+            );
+        }
+
+        require!(
+            amend_args.shipment_release_bps <= 10_000,
+            GrpxProtocolError::InvalidReleaseSchedule
+        );
+
+        if let Some(cold_chain) = &amend_args.cold_chain {
+            require!(
+                cold_chain.is_valid(),
+                GrpxProtocolError::InvalidColdChainTerms
+            );
+        }
+
+        if let Some(geofence) = &amend_args.geofence {
+            require!(geofence.is_valid(), GrpxProtocolError::InvalidGeofence);
+        }
+
+        if let Some(barter) = &amend_args.barter {
+            require!(barter.is_valid(), GrpxProtocolError::InvalidBarterTerms);
+        }
+
+        self.offer.token_mint_b = self.token_mint_b.key();
+        self.offer.token_b_desired_amount = amend_args.token_b_desired_amount;
+        self.offer.allowlist = amend_args.allowlist;
+        self.offer.expires_at = amend_args.expires_at;
+        self.offer.shipment_release_bps = amend_args.shipment_release_bps;
+        self.offer.attestor = amend_args.attestor;
+        self.offer.cold_chain = amend_args.cold_chain;
+        self.offer.requires_custody = amend_args.requires_custody;
+        self.offer.geofence = amend_args.geofence;
+        self.offer.barter = amend_args.barter;
+        self.offer.version = self
+            .offer
+            .version
+            .checked_add(1)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
+
+        self.relist(bumps)
+    }
+
+    fn relist(&mut self, bumps: &AmendOfferBumps) -> Result<()> {
+        if self.offer.listed {
+            return Ok(());
+        }
+
+        require!(
+            self.listing.offer == Pubkey::default() || self.listing.offer == self.offer.key(),
+            GrpxProtocolError::ListingTaken
+        );
+
+        self.listing.set_inner(Listing {
+            token_mint_a: self.offer.token_mint_a,
+            producer: self.producer.key(),
+            offer: self.offer.key(),
+            bump: bumps.listing,
+        });
+        self.offer.listed = true;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use crate::error::GrpxProtocolError;
+use anchor_lang::prelude::*;
+use std::collections::BTreeSet;
+
+/// Confirms or refunds several offers in one transaction. `remaining_accounts` holds one entry
+/// per offer: the accounts of a single `confirm` or `refund`, in the same order and checked
+/// against the same constraints, followed by that offer's bundle and creator accounts. Each
+/// entry carries its own initiator, so the batch is only as permissive as the single calls.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SettleMany<'info> {
+    pub initiator: Signer<'info>,
+}
+
+impl<'info> SettleMany<'info> {
+    pub fn next_entry<T>(
+        program_id: &Pubkey,
+        accounts: &mut &'info [AccountInfo<'info>],
+        extra_account_count: u8,
+    ) -> Result<(T, T::Bumps, &'info [AccountInfo<'info>])>
+    where
+        T: Accounts<'info, T::Bumps> + anchor_lang::Bumps,
+        T::Bumps: Default,
+    {
+        let mut bumps = T::Bumps::default();
+        let entry = T::try_accounts(program_id, accounts, &[], &mut bumps, &mut BTreeSet::new())?;
+
+        let extra_account_count = extra_account_count as usize;
+        require!(
+            accounts.len() >= extra_account_count,
+            GrpxProtocolError::InvalidBatchAccounts
+        );
+        let (extra_accounts, rest) = accounts.split_at(extra_account_count);
+        *accounts = rest;
+
+        Ok((entry, bumps, extra_accounts))
+    }
+
+    pub fn check_consumed(accounts: &[AccountInfo]) -> Result<()> {
+        require!(accounts.is_empty(), GrpxProtocolError::InvalidBatchAccounts);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{
+    constants::{BARTER_PICK_ACCOUNTS, BUNDLE_ACCOUNTS_PER_MINT},
+    error::GrpxProtocolError,
+    BarterTerms,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create_idempotent, get_associated_token_address_with_program_id, Create},
+    metadata::{mpl_token_metadata, MetadataAccount},
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TransferChecked,
+    },
+};
+
+/// Remaining accounts describing one extra NFT of a bundle offer, passed as
+/// `[mint, offer vault, counterparty token account]`.
+pub struct BundleItem<'info> {
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub vault: &'info AccountInfo<'info>,
+    pub token_account: &'info AccountInfo<'info>,
+}
+
+impl<'info> BundleItem<'info> {
+    pub fn parse(
+        accounts: &'info [AccountInfo<'info>],
+        token_program: &Pubkey,
+    ) -> Result<Vec<BundleItem<'info>>> {
+        let chunks = accounts.chunks_exact(BUNDLE_ACCOUNTS_PER_MINT);
+        require!(
+            chunks.remainder().is_empty(),
+            GrpxProtocolError::InvalidBundleAccounts
+        );
+
+        chunks
+            .map(|chunk| {
+                require_keys_eq!(
+                    *chunk[0].owner,
+                    *token_program,
+                    GrpxProtocolError::InvalidBundleAccounts
+                );
+
+                Ok(BundleItem {
+                    mint: InterfaceAccount::<Mint>::try_from(&chunk[0])?,
+                    vault: &chunk[1],
+                    token_account: &chunk[2],
+                })
+            })
+            .collect()
+    }
+
+    /// Parses the NFTs a consumer hands over under barter terms: one
+    /// `[mint, destination, consumer token account]` triple per named mint, in order, then
+    /// `[mint, destination, consumer token account, metadata]` per collection pick.
+    pub fn parse_barter(
+        accounts: &'info [AccountInfo<'info>],
+        terms: &BarterTerms,
+        token_program: &Pubkey,
+    ) -> Result<Vec<BundleItem<'info>>> {
+        require!(
+            accounts.len() == terms.account_count(),
+            GrpxProtocolError::InvalidBarterAccounts
+        );
+
+        let (named, picks) = accounts.split_at(terms.mints.len() * BUNDLE_ACCOUNTS_PER_MINT);
+        let mut items = BundleItem::parse(named, token_program)?;
+        for (item, mint) in items.iter().zip(terms.mints.iter()) {
+            require_keys_eq!(
+                item.mint.key(),
+                *mint,
+                GrpxProtocolError::InvalidBarterAccounts
+            );
+        }
+
+        for pick in picks.chunks_exact(BARTER_PICK_ACCOUNTS) {
+            let (triple, metadata) = pick.split_at(BUNDLE_ACCOUNTS_PER_MINT);
+            let collection = terms
+                .collection
+                .ok_or(GrpxProtocolError::InvalidBarterTerms)?;
+
+            let pick_items = BundleItem::parse(triple, token_program)?;
+            for item in &pick_items {
+                item.check_collection(&metadata[0], &collection)?;
+            }
+            items.extend(pick_items);
+        }
+
+        require!(
+            items.iter().all(|item| item.mint.decimals == 0),
+            GrpxProtocolError::InvalidBarterAccounts
+        );
+
+        Ok(items)
+    }
+
+    pub fn check_collection(&self, metadata: &AccountInfo, collection: &Pubkey) -> Result<()> {
+        require_keys_eq!(
+            *metadata.owner,
+            mpl_token_metadata::ID,
+            GrpxProtocolError::NotInCollection
+        );
+
+        let metadata = MetadataAccount::try_deserialize(&mut &metadata.data.borrow()[..])?;
+        require!(
+            metadata.mint == self.mint.key()
+                && metadata
+                    .collection
+                    .as_ref()
+                    .is_some_and(|item| item.verified && item.key == *collection),
+            GrpxProtocolError::NotInCollection
+        );
+
+        Ok(())
+    }
+
+    pub fn deposit(
+        &self,
+        authority: &AccountInfo<'info>,
+        vault_owner: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        associated_token_program: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        let create_accounts = Create {
+            payer: authority.clone(),
+            associated_token: self.vault.clone(),
+            authority: vault_owner.clone(),
+            mint: self.mint.to_account_info(),
+            system_program: system_program.clone(),
+            token_program: token_program.clone(),
+        };
+        create_idempotent(CpiContext::new(
+            associated_token_program.clone(),
+            create_accounts,
+        ))?;
+
+        let accounts = TransferChecked {
+            from: self.token_account.clone(),
+            mint: self.mint.to_account_info(),
+            to: self.vault.clone(),
+            authority: authority.clone(),
+        };
+        let cpi_context = CpiContext::new(token_program.clone(), accounts);
+        transfer_checked(cpi_context, 1, self.mint.decimals)
+    }
+
+    pub fn validate(&self, offer: &Pubkey, owner: &Pubkey, token_program: &Pubkey) -> Result<()> {
+        let mint = self.mint.key();
+
+        require_keys_eq!(
+            self.vault.key(),
+            get_associated_token_address_with_program_id(offer, &mint, token_program),
+            GrpxProtocolError::InvalidBundleAccounts
+        );
+        require_keys_eq!(
+            self.token_account.key(),
+            get_associated_token_address_with_program_id(owner, &mint, token_program),
+            GrpxProtocolError::InvalidBundleAccounts
+        );
+
+        Ok(())
+    }
+
+    pub fn release(
+        &self,
+        token_program: &AccountInfo<'info>,
+        offer: &AccountInfo<'info>,
+        rent_destination: &AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let amount = InterfaceAccount::<TokenAccount>::try_from(self.vault)?.amount;
+
+        let accounts = TransferChecked {
+            from: self.vault.clone(),
+            mint: self.mint.to_account_info(),
+            to: self.token_account.clone(),
+            authority: offer.clone(),
+        };
+        let cpi_context =
+            CpiContext::new_with_signer(token_program.clone(), accounts, signer_seeds);
+        transfer_checked(cpi_context, amount, self.mint.decimals)?;
+
+        let accounts = CloseAccount {
+            account: self.vault.clone(),
+            destination: rent_destination.clone(),
+            authority: offer.clone(),
+        };
+        let cpi_context =
+            CpiContext::new_with_signer(token_program.clone(), accounts, signer_seeds);
+        close_account(cpi_context)
+    }
+}
@@ -0,0 +1,329 @@
+use crate::{
+    constants::ANCHOR_DISCRIMINATOR,
+    error::GrpxProtocolError,
+    events::OfferBought,
+    instructions::{close_vault, pay_creator_royalties, transfer_from_vault, BundleItem},
+    Listing, Offer, OfferStatus, ProducerLedger, Receipt,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::{
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    metadata::Metadata,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BuyOffer<'info> {
+    #[account(mut)]
+    pub producer: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub consumer: Signer<'info>,
+
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = consumer,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = producer,
+        associated_token::token_program = token_program,
+    )]
+    pub producer_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = consumer,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = consumer,
+        associated_token::token_program = token_program,
+    )]
+    pub consumer_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = consumer,
+        associated_token::token_program = token_program,
+    )]
+    pub consumer_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = producer,
+        has_one = token_mint_a,
+        has_one = token_mint_b,
+        constraint = offer.status == OfferStatus::Created @ GrpxProtocolError::InvalidOfferStatus,
+        constraint = !offer.is_expired(Clock::get()?.unix_timestamp) @ GrpxProtocolError::OfferExpired,
+        constraint = !offer.has_delivery_terms() @ GrpxProtocolError::DeliveryRequired,
+        seeds = [b"offer", producer.key().as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = listing.offer == offer.key() @ GrpxProtocolError::InvalidListing,
+        seeds = [b"listing", token_mint_a.key().as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Option<Account<'info, Listing>>,
+
+    #[account(
+        init_if_needed,
+        payer = consumer,
+        space = ANCHOR_DISCRIMINATOR + ProducerLedger::INIT_SPACE,
+        seeds = [b"ledger", producer.key().as_ref()],
+        bump
+    )]
+    pub ledger: Account<'info, ProducerLedger>,
+
+    #[account(
+        init,
+        payer = consumer,
+        space = ANCHOR_DISCRIMINATOR + Receipt::INIT_SPACE,
+        seeds = [
+            b"receipt",
+            producer.key().as_ref(),
+            ledger.next_invoice_number().to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub receipt: Account<'info, Receipt>,
+
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), token_mint_a.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    /// CHECK: Metaplex metadata PDA of token_mint_a, only deserialized when it has been initialized
+    pub metadata_a: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> BuyOffer<'info> {
+    pub fn transfer_barter_to_producer(
+        &mut self,
+        quantity: u64,
+        barter_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let Some(terms) = &self.offer.barter else {
+            return Ok(());
+        };
+        require!(
+            quantity == self.offer.token_a_remaining_amount,
+            GrpxProtocolError::InvalidQuantity
+        );
+
+        let token_program = self.token_program.key();
+        let items = BundleItem::parse_barter(barter_accounts, terms, &token_program)?;
+
+        for item in &items {
+            item.validate(&self.producer.key(), &self.consumer.key(), &token_program)?;
+            item.deposit(
+                &self.consumer.to_account_info(),
+                &self.producer.to_account_info(),
+                &self.token_program.to_account_info(),
+                &self.associated_token_program.to_account_info(),
+                &self.system_program.to_account_info(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn record_receipt(&mut self, quantity: u64, bumps: &BuyOfferBumps) -> Result<()> {
+        let invoice_number = self.ledger.next_invoice_number();
+
+        self.receipt.set_inner(Receipt {
+            invoice_number,
+            producer: self.producer.key(),
+            consumer: self.consumer.key(),
+            offer: self.offer.key(),
+            token_mint_a: self.token_mint_a.key(),
+            token_mint_b: self.token_mint_b.key(),
+            quantity,
+            token_b_amount: self.offer.price_for(quantity)?,
+            breach_penalty_bps: 0,
+            settled_at: Clock::get()?.unix_timestamp,
+            bump: bumps.receipt,
+        });
+
+        self.ledger.producer = self.producer.key();
+        self.ledger.invoice_count = invoice_number;
+        self.ledger.bump = bumps.ledger;
+
+        Ok(())
+    }
+
+    pub fn pay_producer(
+        &mut self,
+        quantity: u64,
+        creator_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let price = self.offer.price_for(quantity)?;
+        let payment_mint = (!self.offer.is_native()).then(|| self.token_mint_b.key());
+
+        let royalties = pay_creator_royalties(
+            &self.metadata_a,
+            price,
+            payment_mint,
+            creator_accounts,
+            |creator_account, amount| self.transfer_from_consumer(creator_account.clone(), amount),
+        )?;
+
+        let producer_account = if self.offer.is_native() {
+            self.producer.to_account_info()
+        } else {
+            self.producer_token_account_b
+                .as_ref()
+                .ok_or(GrpxProtocolError::InvalidPaymentAccounts)?
+                .to_account_info()
+        };
+        let amount = price
+            .checked_sub(royalties)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
+
+        self.transfer_from_consumer(producer_account, amount)
+    }
+
+    pub fn transfer_nft_to_consumer(&mut self, quantity: u64) -> Result<()> {
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
+
+        transfer_from_vault(
+            &self.vault_token_account_a,
+            &self.token_mint_a,
+            self.consumer_token_account_a.to_account_info(),
+            self.offer.to_account_info(),
+            quantity,
+            self.token_program.to_account_info(),
+            &[&seeds],
+        )?;
+
+        self.offer.token_a_remaining_amount = self
+            .offer
+            .token_a_remaining_amount
+            .checked_sub(quantity)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn transfer_bundle_to_consumer(
+        &mut self,
+        bundle_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let token_program = self.token_program.key();
+        let items = BundleItem::parse(bundle_accounts, &token_program)?;
+
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
+        let signer_seeds = [&seeds[..]];
+
+        for (item, bundle_mint) in items.iter().zip(self.offer.bundle_mints.iter()) {
+            require_keys_eq!(
+                item.mint.key(),
+                *bundle_mint,
+                GrpxProtocolError::InvalidBundleAccounts
+            );
+            item.validate(&self.offer.key(), &self.consumer.key(), &token_program)?;
+
+            let create_accounts = Create {
+                payer: self.consumer.to_account_info(),
+                associated_token: item.token_account.clone(),
+                authority: self.consumer.to_account_info(),
+                mint: item.mint.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            };
+            create_idempotent(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                create_accounts,
+            ))?;
+
+            item.release(
+                &self.token_program.to_account_info(),
+                &self.offer.to_account_info(),
+                &self.producer.to_account_info(),
+                &signer_seeds,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn bought_event(&self) -> OfferBought {
+        OfferBought {
+            offer: self.offer.key(),
+            producer: self.receipt.producer,
+            consumer: self.receipt.consumer,
+            token_mint_a: self.receipt.token_mint_a,
+            token_mint_b: self.receipt.token_mint_b,
+            quantity: self.receipt.quantity,
+            token_b_amount: self.receipt.token_b_amount,
+            invoice_number: self.receipt.invoice_number,
+            token_a_remaining_amount: self.offer.token_a_remaining_amount,
+            status: self.offer.status,
+        }
+    }
+
+    pub fn close_vault(&mut self) -> Result<()> {
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
+
+        close_vault(
+            self.vault_token_account_a.to_account_info(),
+            self.producer.to_account_info(),
+            self.offer.to_account_info(),
+            self.token_program.to_account_info(),
+            &[&seeds],
+        )?;
+
+        self.offer.status = OfferStatus::Completed;
+        self.offer.close(self.producer.to_account_info())
+    }
+
+    fn transfer_from_consumer(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        if self.offer.is_native() {
+            let accounts = Transfer {
+                from: self.consumer.to_account_info(),
+                to,
+            };
+            let cpi_context = CpiContext::new(self.system_program.to_account_info(), accounts);
+
+            return transfer(cpi_context, amount);
+        }
+
+        let consumer_token_account_b = self
+            .consumer_token_account_b
+            .as_ref()
+            .ok_or(GrpxProtocolError::InvalidPaymentAccounts)?;
+
+        let accounts = TransferChecked {
+            from: consumer_token_account_b.to_account_info(),
+            mint: self.token_mint_b.to_account_info(),
+            to,
+            authority: self.consumer.to_account_info(),
+        };
+        let cpi_context = CpiContext::new(self.token_program.to_account_info(), accounts);
+
+        transfer_checked(cpi_context, amount, self.token_mint_b.decimals)
+    }
+}
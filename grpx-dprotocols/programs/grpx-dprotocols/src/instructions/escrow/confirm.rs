@@ -1,20 +1,37 @@
-use crate::{error::GrpxProtocolError, Offer, OfferStatus};
+use crate::{
+    constants::ANCHOR_DISCRIMINATOR,
+    error::GrpxProtocolError,
+    events::OfferConfirmed,
+    instructions::{
+        close_vault, escrowed_payment, pay_creator_royalties, pay_from_escrow, remove_listing,
+        transfer_from_vault, wipe_shipping_details, BundleItem,
+    },
+    CustodyChain, Listing, Offer, OfferStatus, ProducerLedger, Receipt,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token_interface::{
-        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
-        TransferChecked,
-    },
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    metadata::Metadata,
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ConfirmOffer<'info> {
     #[account(mut)]
     pub producer: SystemAccount<'info>,
 
     #[account(mut)]
-    pub consumer: Signer<'info>,
+    pub consumer: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = (
+            initiator.key() == consumer.key() ||
+            offer.is_release_due(Clock::get()?.unix_timestamp)
+        ) @ GrpxProtocolError::UnauthorizedConfirmation
+    )]
+    pub initiator: Signer<'info>,
 
     pub token_mint_a: InterfaceAccount<'info, Mint>,
 
@@ -22,29 +39,39 @@ pub struct ConfirmOffer<'info> {
 
     #[account(
         init_if_needed,
-        payer = consumer,
+        payer = initiator,
         associated_token::mint = token_mint_b,
         associated_token::authority = producer,
         associated_token::token_program = token_program,
     )]
-    pub producer_token_account_b: InterfaceAccount<'info, TokenAccount>,
+    pub producer_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
-        payer = consumer,
+        payer = initiator,
         associated_token::mint = token_mint_a,
         associated_token::authority = consumer,
         associated_token::token_program = token_program,
     )]
     pub consumer_token_account_a: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        init_if_needed,
+        payer = initiator,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = consumer,
+        associated_token::token_program = token_program,
+    )]
+    pub consumer_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
     #[account(
         mut,
-        close = consumer,
         has_one = producer,
+        has_one = token_mint_a,
         has_one = token_mint_b,
         constraint = offer.consumer == Some(consumer.key()) @ GrpxProtocolError::UnauthorizedConfirmation,
-        constraint = offer.status == OfferStatus::Accepted @ GrpxProtocolError::InvalidOfferStatus,
+        constraint = offer.is_awaiting_confirmation() @ GrpxProtocolError::InvalidOfferStatus,
+        constraint = offer.is_location_cleared() @ GrpxProtocolError::LocationNotVerified,
         seeds = [b"offer", producer.key().as_ref(), offer.id.to_le_bytes().as_ref()],
         bump = offer.bump
     )]
@@ -64,113 +91,402 @@ pub struct ConfirmOffer<'info> {
         associated_token::authority = offer,
         associated_token::token_program = token_program,
     )]
-    pub vault_token_account_b: InterfaceAccount<'info, TokenAccount>,
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = listing.offer == offer.key() @ GrpxProtocolError::InvalidListing,
+        seeds = [b"listing", token_mint_a.key().as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Option<Account<'info, Listing>>,
+
+    #[account(
+        init_if_needed,
+        payer = initiator,
+        space = ANCHOR_DISCRIMINATOR + ProducerLedger::INIT_SPACE,
+        seeds = [b"ledger", producer.key().as_ref()],
+        bump
+    )]
+    pub ledger: Account<'info, ProducerLedger>,
+
+    #[account(
+        init,
+        payer = initiator,
+        space = ANCHOR_DISCRIMINATOR + Receipt::INIT_SPACE,
+        seeds = [
+            b"receipt",
+            producer.key().as_ref(),
+            ledger.next_invoice_number().to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub receipt: Account<'info, Receipt>,
+
+    #[account(
+        seeds = [b"custody", token_mint_a.key().as_ref()],
+        bump = custody_chain.bump
+    )]
+    pub custody_chain: Option<Account<'info, CustodyChain>>,
 
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), token_mint_a.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump,
+    )]
+    /// CHECK: Metaplex metadata PDA of token_mint_a, only deserialized when it has been initialized
+    pub metadata_a: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> ConfirmOffer<'info> {
-    pub fn transfer_nft_to_consumer(&mut self) -> Result<()> {
-        let seeds = &[
-            b"offer",
-            self.producer.to_account_info().key.as_ref(),
-            &self.offer.id.to_le_bytes()[..],
-            &[self.offer.bump],
-        ];
-        let signer_seeds = [&seeds[..]];
+    /// Settles the accepted fill. `remaining_accounts` holds the bundle item triples, then the
+    /// escrowed barter NFT triples, then the creator royalty accounts.
+    pub fn settle(
+        &mut self,
+        bumps: &ConfirmOfferBumps,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<OfferConfirmed> {
+        let (bundle_accounts, remaining_accounts) =
+            self.offer.split_bundle_accounts(remaining_accounts)?;
+        let (barter_accounts, creator_accounts) =
+            self.offer.split_barter_accounts(remaining_accounts)?;
 
-        let accounts = TransferChecked {
-            from: self.vault_token_account_a.to_account_info(),
-            mint: self.token_mint_a.to_account_info(),
-            to: self.consumer_token_account_a.to_account_info(),
-            authority: self.offer.to_account_info(),
-        };
+        self.check_final_custodian()?;
+        wipe_shipping_details(&mut self.offer, &self.consumer.to_account_info())?;
+        self.record_receipt(bumps)?;
+        self.refund_breach_penalty()?;
+        self.pay_creator_royalties(creator_accounts)?;
+        self.transfer_payment_to_producer()?;
+        self.transfer_nft_to_consumer()?;
 
-        let cpi_context = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
-            accounts,
-            &signer_seeds,
-        );
+        if self.offer.is_filled() {
+            self.transfer_bundle_to_consumer(bundle_accounts)?;
+            self.transfer_barter_to_producer(barter_accounts)?;
+            remove_listing(
+                &mut self.offer,
+                self.listing.as_ref(),
+                &self.producer.to_account_info(),
+            )?;
+            self.close_vaults()?;
+        } else {
+            self.reopen_offer()?;
+        }
+
+        Ok(self.confirmed_event())
+    }
 
-        transfer_checked(
-            cpi_context,
-            self.vault_token_account_a.amount,
-            self.token_mint_a.decimals,
+    pub fn transfer_nft_to_consumer(&mut self) -> Result<()> {
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
+
+        transfer_from_vault(
+            &self.vault_token_account_a,
+            &self.token_mint_a,
+            self.consumer_token_account_a.to_account_info(),
+            self.offer.to_account_info(),
+            self.offer.token_a_accepted_amount,
+            self.token_program.to_account_info(),
+            &[&seeds],
         )?;
 
-        self.offer.status = OfferStatus::Accepted;
-        self.offer.consumer = Some(self.consumer.key());
+        self.offer.token_a_remaining_amount = self
+            .offer
+            .token_a_remaining_amount
+            .checked_sub(self.offer.token_a_accepted_amount)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
 
         Ok(())
     }
 
-    pub fn transfer_sol_to_producer(&mut self) -> Result<()> {
-        let seeds = &[
-            b"offer",
-            self.producer.to_account_info().key.as_ref(),
-            &self.offer.id.to_le_bytes()[..],
-            &[self.offer.bump],
-        ];
+    pub fn transfer_bundle_to_consumer(
+        &mut self,
+        bundle_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let token_program = self.token_program.key();
+        let items = BundleItem::parse(bundle_accounts, &token_program)?;
+
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
         let signer_seeds = [&seeds[..]];
 
-        let accounts = TransferChecked {
-            from: self.vault_token_account_b.to_account_info(),
-            mint: self.token_mint_b.to_account_info(),
-            to: self.producer_token_account_b.to_account_info(),
-            authority: self.offer.to_account_info(),
-        };
+        for (item, bundle_mint) in items.iter().zip(self.offer.bundle_mints.iter()) {
+            require_keys_eq!(
+                item.mint.key(),
+                *bundle_mint,
+                GrpxProtocolError::InvalidBundleAccounts
+            );
+            item.validate(&self.offer.key(), &self.consumer.key(), &token_program)?;
 
-        let cpi_context = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
-            accounts,
-            &signer_seeds,
+            let create_accounts = Create {
+                payer: self.initiator.to_account_info(),
+                associated_token: item.token_account.clone(),
+                authority: self.consumer.to_account_info(),
+                mint: item.mint.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            };
+            create_idempotent(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                create_accounts,
+            ))?;
+
+            item.release(
+                &self.token_program.to_account_info(),
+                &self.offer.to_account_info(),
+                &self.consumer.to_account_info(),
+                &signer_seeds,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn transfer_barter_to_producer(
+        &mut self,
+        barter_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let token_program = self.token_program.key();
+        let items = BundleItem::parse(barter_accounts, &token_program)?;
+
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
+        let signer_seeds = [&seeds[..]];
+
+        for (item, barter_mint) in items.iter().zip(self.offer.barter_mints.iter()) {
+            require_keys_eq!(
+                item.mint.key(),
+                *barter_mint,
+                GrpxProtocolError::InvalidBarterAccounts
+            );
+            item.validate(&self.offer.key(), &self.producer.key(), &token_program)?;
+
+            let create_accounts = Create {
+                payer: self.initiator.to_account_info(),
+                associated_token: item.token_account.clone(),
+                authority: self.producer.to_account_info(),
+                mint: item.mint.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            };
+            create_idempotent(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                create_accounts,
+            ))?;
+
+            item.release(
+                &self.token_program.to_account_info(),
+                &self.offer.to_account_info(),
+                &self.consumer.to_account_info(),
+                &signer_seeds,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn record_receipt(&mut self, bumps: &ConfirmOfferBumps) -> Result<()> {
+        let invoice_number = self.ledger.next_invoice_number();
+
+        self.receipt.set_inner(Receipt {
+            invoice_number,
+            producer: self.producer.key(),
+            consumer: self.consumer.key(),
+            offer: self.offer.key(),
+            token_mint_a: self.token_mint_a.key(),
+            token_mint_b: self.token_mint_b.key(),
+            quantity: self.offer.token_a_accepted_amount,
+            token_b_amount: self.offer.price_for(self.offer.token_a_accepted_amount)?,
+            breach_penalty_bps: self.offer.breach_penalty_bps,
+            settled_at: Clock::get()?.unix_timestamp,
+            bump: bumps.receipt,
+        });
+
+        self.ledger.producer = self.producer.key();
+        self.ledger.invoice_count = invoice_number;
+        self.ledger.bump = bumps.ledger;
+
+        Ok(())
+    }
+
+    /// Built from the receipt, since a settled partial fill has already reset the offer.
+    pub fn confirmed_event(&self) -> OfferConfirmed {
+        OfferConfirmed {
+            offer: self.offer.key(),
+            producer: self.receipt.producer,
+            consumer: self.receipt.consumer,
+            token_mint_a: self.receipt.token_mint_a,
+            token_mint_b: self.receipt.token_mint_b,
+            quantity: self.receipt.quantity,
+            token_b_amount: self.receipt.token_b_amount,
+            invoice_number: self.receipt.invoice_number,
+            token_a_remaining_amount: self.offer.token_a_remaining_amount,
+            status: self.offer.status,
+        }
+    }
+
+    pub fn check_final_custodian(&self) -> Result<()> {
+        if !self.offer.requires_custody {
+            return Ok(());
+        }
+
+        let custodian = self
+            .custody_chain
+            .as_ref()
+            .map(|custody_chain| custody_chain.custodian);
+        require!(
+            custodian == Some(self.consumer.key()),
+            GrpxProtocolError::CustodyNotTransferred
         );
 
-        transfer_checked(
-            cpi_context,
-            self.vault_token_account_b.amount,
-            self.token_mint_b.decimals,
+        Ok(())
+    }
+
+    pub fn refund_breach_penalty(&mut self) -> Result<()> {
+        if self.offer.breach_penalty_bps == 0 {
+            return Ok(());
+        }
+
+        let penalty = (self.escrowed_payment()? as u128 * self.offer.breach_penalty_bps as u128
+            / 10_000) as u64;
+        if penalty == 0 {
+            return Ok(());
+        }
+
+        let consumer_account = if self.offer.is_native() {
+            self.consumer.to_account_info()
+        } else {
+            self.consumer_token_account_b
+                .as_ref()
+                .ok_or(GrpxProtocolError::InvalidPaymentAccounts)?
+                .to_account_info()
+        };
+        self.pay_from_escrow(consumer_account, penalty)?;
+
+        if let Some(vault_token_account_b) = self.vault_token_account_b.as_mut() {
+            vault_token_account_b.reload()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn pay_creator_royalties(
+        &mut self,
+        creator_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let proceeds = self.escrowed_payment()?;
+        let payment_mint = (!self.offer.is_native()).then(|| self.token_mint_b.key());
+
+        pay_creator_royalties(
+            &self.metadata_a,
+            proceeds,
+            payment_mint,
+            creator_accounts,
+            |creator_account, amount| self.pay_from_escrow(creator_account.clone(), amount),
         )?;
 
+        if let Some(vault_token_account_b) = self.vault_token_account_b.as_mut() {
+            vault_token_account_b.reload()?;
+        }
+
         Ok(())
     }
 
-    pub fn close_vaults(&mut self) -> Result<()> {
-        let seeds = &[
-            b"offer",
-            self.producer.to_account_info().key.as_ref(),
-            &self.offer.id.to_le_bytes()[..],
-            &[self.offer.bump],
-        ];
+    pub fn transfer_payment_to_producer(&mut self) -> Result<()> {
+        let producer_account = if self.offer.is_native() {
+            self.producer.to_account_info()
+        } else {
+            self.producer_token_account_b
+                .as_ref()
+                .ok_or(GrpxProtocolError::InvalidPaymentAccounts)?
+                .to_account_info()
+        };
+        let amount = self.escrowed_payment()?;
+
+        self.pay_from_escrow(producer_account, amount)
+    }
+
+    fn escrowed_payment(&self) -> Result<u64> {
+        escrowed_payment(
+            &self.offer.to_account_info(),
+            self.vault_token_account_b.as_ref(),
+            &self.token_mint_b.key(),
+        )
+    }
+
+    fn pay_from_escrow(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
         let signer_seeds = [&seeds[..]];
 
-        let producer_accounts = CloseAccount {
-            account: self.vault_token_account_a.to_account_info(),
-            destination: self.consumer.to_account_info(),
-            authority: self.offer.to_account_info(),
-        };
-        let producer_cpi_context = CpiContext::new_with_signer(
+        pay_from_escrow(
+            self.offer.to_account_info(),
+            self.vault_token_account_b.as_ref(),
+            &self.token_mint_b,
+            to,
+            amount,
             self.token_program.to_account_info(),
-            producer_accounts,
             &signer_seeds,
-        );
+        )
+    }
 
-        let consumer_accounts = CloseAccount {
-            account: self.vault_token_account_b.to_account_info(),
-            destination: self.consumer.to_account_info(),
-            authority: self.offer.to_account_info(),
-        };
-        let consumer_cpi_context = CpiContext::new_with_signer(
+    pub fn close_vaults(&mut self) -> Result<()> {
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
+
+        close_vault(
+            self.vault_token_account_a.to_account_info(),
+            self.consumer.to_account_info(),
+            self.offer.to_account_info(),
             self.token_program.to_account_info(),
-            consumer_accounts,
-            &signer_seeds,
-        );
+            &[&seeds],
+        )?;
+
+        if let Some(vault_token_account_b) = &self.vault_token_account_b {
+            close_vault(
+                vault_token_account_b.to_account_info(),
+                self.consumer.to_account_info(),
+                self.offer.to_account_info(),
+                self.token_program.to_account_info(),
+                &[&seeds],
+            )?;
+        }
 
-        close_account(producer_cpi_context)?;
-        close_account(consumer_cpi_context)?;
         self.offer.status = OfferStatus::Completed;
+        self.offer.close(self.consumer.to_account_info())
+    }
+
+    pub fn reopen_offer(&mut self) -> Result<()> {
+        if let Some(vault_token_account_b) = &self.vault_token_account_b {
+            let id = self.offer.id.to_le_bytes();
+            let seeds = self.offer.signer_seeds(&id);
+
+            close_vault(
+                vault_token_account_b.to_account_info(),
+                self.consumer.to_account_info(),
+                self.offer.to_account_info(),
+                self.token_program.to_account_info(),
+                &[&seeds],
+            )?;
+        }
+
+        self.offer.consumer = None;
+        self.offer.token_a_accepted_amount = 0;
+        self.offer.carrier = None;
+        self.offer.tracking_hash = None;
+        self.offer.shipped_at = None;
+        self.offer.delivered_at = None;
+        self.offer.delivery_attested = false;
+        self.offer.breach_penalty_bps = 0;
+        self.offer.delivery_code_hash = None;
+        self.offer.delivery_code_revealed = false;
+        self.offer.delivery_code_expires_at = None;
+        self.offer.location_verified = false;
+        self.offer.status = OfferStatus::Created;
 
         Ok(())
     }
@@ -0,0 +1,35 @@
+use crate::{error::GrpxProtocolError, Listing, Offer, OfferStatus};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct DelistOffer<'info> {
+    #[account(mut)]
+    pub producer: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = producer,
+        constraint = offer.status == OfferStatus::Created @ GrpxProtocolError::InvalidOfferStatus,
+        constraint = offer.is_expired(Clock::get()?.unix_timestamp) @ GrpxProtocolError::OfferNotExpired,
+        seeds = [b"offer", producer.key().as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        close = producer,
+        has_one = offer,
+        seeds = [b"listing", offer.token_mint_a.as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, Listing>,
+}
+
+impl<'info> DelistOffer<'info> {
+    pub fn delist(&mut self) -> Result<()> {
+        self.offer.listed = false;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,19 @@
 pub mod accept;
+pub mod amend;
+pub mod batch;
+pub mod bundle;
+pub mod buy;
 pub mod confirm;
+pub mod delist;
 pub mod open;
 pub mod refund;
 
 pub use accept::*;
+pub use amend::*;
+pub use batch::*;
+pub use bundle::*;
+pub use buy::*;
 pub use confirm::*;
+pub use delist::*;
 pub use open::*;
 pub use refund::*;
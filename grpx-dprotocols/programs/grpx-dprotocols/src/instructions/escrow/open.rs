@@ -1,13 +1,16 @@
-use crate::constants::ANCHOR_DISCRIMINATOR;
-use crate::state::{Offer, OfferStatus};
+use crate::constants::{ANCHOR_DISCRIMINATOR, MAX_BUNDLE_MINTS};
+use crate::error::GrpxProtocolError;
+use crate::events::OfferOpened;
+use crate::instructions::BundleItem;
+use crate::state::{Listing, Offer, OfferAllowlist, OfferStatus, ProducerCounter};
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
+    associated_token::{create, AssociatedToken, Create},
     token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(id: u64)]
 pub struct CreateOffer<'info> {
     #[account(mut)]
     pub producer: Signer<'info>,
@@ -26,11 +29,20 @@ pub struct CreateOffer<'info> {
     )]
     pub producer_token_account_a: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        init_if_needed,
+        payer = producer,
+        space = ANCHOR_DISCRIMINATOR + ProducerCounter::INIT_SPACE,
+        seeds = [b"producer_counter", producer.key().as_ref()],
+        bump
+    )]
+    pub producer_counter: Account<'info, ProducerCounter>,
+
     #[account(
         init,
         payer = producer,
         space = ANCHOR_DISCRIMINATOR + Offer::INIT_SPACE,
-        seeds = [b"offer", producer.key().as_ref(), id.to_le_bytes().as_ref()],
+        seeds = [b"offer", producer.key().as_ref(), producer_counter.offer_count.to_le_bytes().as_ref()],
         bump
     )]
     pub offer: Account<'info, Offer>,
@@ -44,6 +56,15 @@ pub struct CreateOffer<'info> {
     )]
     pub vault_token_account_a: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        init,
+        payer = producer,
+        space = ANCHOR_DISCRIMINATOR + Listing::INIT_SPACE,
+        seeds = [b"listing", token_mint_a.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -52,25 +73,90 @@ pub struct CreateOffer<'info> {
 impl<'info> CreateOffer<'info> {
     pub fn open_vault(
         &mut self,
-        id: u64,
+        token_a_offered_amount: u64,
         token_b_desired_amount: u64,
+        allowlist: Option<OfferAllowlist>,
+        expires_at: Option<i64>,
         bumps: &CreateOfferBumps,
     ) -> Result<()> {
+        require!(
+            token_a_offered_amount > 0,
+            GrpxProtocolError::InvalidQuantity
+        );
+
+        if let Some(expires_at) = expires_at {
+            require!(
+                expires_at > Clock::get()?.unix_timestamp,
+                GrpxProtocolError::InvalidExpiry
+            );
+        }
+
+        let id = self
+            .producer_counter
+            .assign_offer_id(self.producer.key(), bumps.producer_counter)?;
+
         self.offer.set_inner(Offer {
             id,
             producer: self.producer.key(),
             consumer: None,
             token_mint_a: self.token_mint_a.key(),
             token_mint_b: self.token_mint_b.key(),
-            token_a_offered_amount: 1,
+            token_a_offered_amount,
+            token_a_remaining_amount: token_a_offered_amount,
+            token_a_accepted_amount: 0,
             token_b_desired_amount,
+            bundle_mints: Vec::new(),
+            allowlist,
+            expires_at,
+            version: 0,
+            shipment_release_bps: 0,
+            carrier: None,
+            tracking_hash: None,
+            shipped_at: None,
+            attestor: None,
+            delivered_at: None,
+            delivery_attested: false,
+            cold_chain: None,
+            breach_penalty_bps: 0,
+            delivery_code_hash: None,
+            delivery_code_revealed: false,
+            delivery_code_expires_at: None,
+            requires_custody: false,
+            geofence: None,
+            location_verified: false,
+            shipping_details: None,
+            listed: true,
+            barter: None,
+            barter_mints: Vec::new(),
+            awarded: false,
             status: OfferStatus::Created,
             bump: bumps.offer,
         });
 
+        self.listing.set_inner(Listing {
+            token_mint_a: self.token_mint_a.key(),
+            producer: self.producer.key(),
+            offer: self.offer.key(),
+            bump: bumps.listing,
+        });
+
         Ok(())
     }
 
+    pub fn opened_event(&self) -> OfferOpened {
+        OfferOpened {
+            offer: self.offer.key(),
+            id: self.offer.id,
+            producer: self.producer.key(),
+            token_mint_a: self.token_mint_a.key(),
+            token_mint_b: self.token_mint_b.key(),
+            token_a_offered_amount: self.offer.token_a_offered_amount,
+            token_b_desired_amount: self.offer.token_b_desired_amount,
+            expires_at: self.offer.expires_at,
+            status: self.offer.status,
+        }
+    }
+
     pub fn deposit_nft_to_vault(&mut self, token_a_offered_amount: u64) -> Result<()> {
         let cpi_program = self.token_program.to_account_info();
 
@@ -84,4 +170,50 @@ impl<'info> CreateOffer<'info> {
         let cpi_ctx = CpiContext::new(cpi_program, transfer_accounts);
         transfer_checked(cpi_ctx, token_a_offered_amount, self.token_mint_a.decimals)
     }
+
+    pub fn deposit_bundle_to_vaults(
+        &mut self,
+        bundle_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let token_program = self.token_program.key();
+        let items = BundleItem::parse(bundle_accounts, &token_program)?;
+        require!(
+            items.len() <= MAX_BUNDLE_MINTS,
+            GrpxProtocolError::BundleTooLarge
+        );
+
+        for item in &items {
+            require!(
+                item.mint.decimals == 0,
+                GrpxProtocolError::InvalidBundleAccounts
+            );
+            item.validate(&self.offer.key(), &self.producer.key(), &token_program)?;
+
+            let create_accounts = Create {
+                payer: self.producer.to_account_info(),
+                associated_token: item.vault.clone(),
+                authority: self.offer.to_account_info(),
+                mint: item.mint.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            };
+            create(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                create_accounts,
+            ))?;
+
+            let transfer_accounts = TransferChecked {
+                from: item.token_account.clone(),
+                mint: item.mint.to_account_info(),
+                to: item.vault.clone(),
+                authority: self.producer.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+            transfer_checked(cpi_ctx, 1, item.mint.decimals)?;
+
+            self.offer.bundle_mints.push(item.mint.key());
+        }
+
+        Ok(())
+    }
 }
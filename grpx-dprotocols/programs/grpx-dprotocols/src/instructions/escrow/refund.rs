@@ -1,13 +1,19 @@
-use crate::{error::GrpxProtocolError, Offer, OfferStatus};
+use crate::{
+    error::GrpxProtocolError,
+    events::OfferRefunded,
+    instructions::{
+        close_vault, escrowed_lamports, escrowed_payment, pay_from_escrow, remove_listing,
+        transfer_from_vault, wipe_shipping_details, BundleItem,
+    },
+    Listing, Offer, OfferStatus,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token_interface::{
-        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
-        TransferChecked,
-    },
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct RefundOffer<'info> {
     #[account(mut)]
@@ -17,7 +23,7 @@ pub struct RefundOffer<'info> {
     pub consumer: SystemAccount<'info>,
 
     #[account(mut, constraint = (
-        initiator.key() == producer.key() || 
+        initiator.key() == producer.key() ||
         initiator.key() == consumer.key()
     ) @ GrpxProtocolError::UnauthorizedRefund)]
     pub initiator: Signer<'info>,
@@ -40,7 +46,7 @@ pub struct RefundOffer<'info> {
         associated_token::authority = consumer,
         associated_token::token_program = token_program,
     )]
-    pub consumer_token_account_b: InterfaceAccount<'info, TokenAccount>,
+    pub consumer_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
@@ -48,7 +54,14 @@ pub struct RefundOffer<'info> {
         has_one = producer,
         has_one = token_mint_a,
         has_one = token_mint_b,
-        constraint = (offer.status == OfferStatus::Created || offer.status == OfferStatus::Accepted) @ GrpxProtocolError::InvalidOfferStatus,
+        constraint = (offer.consumer.is_none() || offer.consumer == Some(consumer.key())) @ GrpxProtocolError::UnauthorizedRefund,
+        constraint = (offer.status != OfferStatus::Created || initiator.key() == producer.key()) @ GrpxProtocolError::UnauthorizedRefund,
+        constraint = (
+            offer.status == OfferStatus::Created ||
+            (offer.status == OfferStatus::Accepted && (!offer.awarded || initiator.key() == producer.key())) ||
+            (offer.status == OfferStatus::Disputed && initiator.key() == producer.key()) ||
+            (offer.is_delivery_overdue(Clock::get()?.unix_timestamp) && initiator.key() == consumer.key())
+        ) @ GrpxProtocolError::InvalidOfferStatus,
         seeds = [b"offer", producer.key().as_ref(), offer.id.to_le_bytes().as_ref()],
         bump = offer.bump
     )]
@@ -70,128 +83,243 @@ pub struct RefundOffer<'info> {
     )]
     pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(
+        mut,
+        constraint = listing.offer == offer.key() @ GrpxProtocolError::InvalidListing,
+        seeds = [b"listing", token_mint_a.key().as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Option<Account<'info, Listing>>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> RefundOffer<'info> {
+    /// `remaining_accounts` holds the bundle item triples, then the escrowed barter NFT triples.
+    pub fn refund(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<OfferRefunded> {
+        let (bundle_accounts, remaining_accounts) =
+            self.offer.split_bundle_accounts(remaining_accounts)?;
+        let (barter_accounts, _) = self.offer.split_barter_accounts(remaining_accounts)?;
+
+        let event = self.refunded_event()?;
+        wipe_shipping_details(&mut self.offer, &self.consumer.to_account_info())?;
+        self.process_refund()?;
+        self.return_bundle_to_producer(bundle_accounts)?;
+        self.return_barter_to_consumer(barter_accounts)?;
+        remove_listing(
+            &mut self.offer,
+            self.listing.as_ref(),
+            &self.producer.to_account_info(),
+        )?;
+        self.close_vaults()?;
+
+        Ok(event)
+    }
+
+    /// Captured before the refund moves anything out of escrow.
+    pub fn refunded_event(&self) -> Result<OfferRefunded> {
+        let token_b_amount = match self.offer.status {
+            OfferStatus::Created => 0,
+            _ if self.offer.is_native() => escrowed_lamports(&self.offer.to_account_info())?,
+            _ => self
+                .vault_token_account_b
+                .as_ref()
+                .map_or(0, |vault_token_account_b| vault_token_account_b.amount),
+        };
+
+        Ok(OfferRefunded {
+            offer: self.offer.key(),
+            producer: self.producer.key(),
+            consumer: self.offer.consumer,
+            initiator: self.initiator.key(),
+            token_mint_a: self.token_mint_a.key(),
+            token_mint_b: self.token_mint_b.key(),
+            token_a_amount: self.vault_token_account_a.amount,
+            token_b_amount,
+            status: OfferStatus::Refunded,
+        })
+    }
+
     pub fn process_refund(&mut self) -> Result<()> {
         match self.offer.status {
             OfferStatus::Created => {
                 self.return_nft_to_producer()?;
-            },
-            OfferStatus::Accepted => {
+            }
+            OfferStatus::Accepted
+            | OfferStatus::Shipped
+            | OfferStatus::InTransit
+            | OfferStatus::Delivered
+            | OfferStatus::Disputed => {
                 self.return_nft_to_producer()?;
-                if let Some(vault_token_b) = &self.vault_token_account_b {
-                    if vault_token_b.amount > 0 {
-                        self.return_sol_to_consumer()?;
-                    }
-                }
-            },
-            _ => {
-                return Err(GrpxProtocolError::InvalidOfferStatus.into())
+                self.return_payment_to_consumer()?;
             }
+            _ => return Err(GrpxProtocolError::InvalidOfferStatus.into()),
         }
-        
+
         Ok(())
     }
-    
+
     pub fn return_nft_to_producer(&mut self) -> Result<()> {
-        let seeds = &[
-            b"offer",
-            self.producer.to_account_info().key.as_ref(),
-            &self.offer.id.to_le_bytes()[..],
-            &[self.offer.bump],
-        ];
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
+
+        transfer_from_vault(
+            &self.vault_token_account_a,
+            &self.token_mint_a,
+            self.producer_token_account_a.to_account_info(),
+            self.offer.to_account_info(),
+            self.vault_token_account_a.amount,
+            self.token_program.to_account_info(),
+            &[&seeds],
+        )
+    }
+
+    pub fn return_barter_to_consumer(
+        &mut self,
+        barter_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let token_program = self.token_program.key();
+        let items = BundleItem::parse(barter_accounts, &token_program)?;
+
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
         let signer_seeds = [&seeds[..]];
 
-        let accounts = TransferChecked {
-            from: self.vault_token_account_a.to_account_info(),
-            mint: self.token_mint_a.to_account_info(),
-            to: self.producer_token_account_a.to_account_info(),
-            authority: self.offer.to_account_info(),
-        };
+        for (item, barter_mint) in items.iter().zip(self.offer.barter_mints.iter()) {
+            require_keys_eq!(
+                item.mint.key(),
+                *barter_mint,
+                GrpxProtocolError::InvalidBarterAccounts
+            );
+            item.validate(&self.offer.key(), &self.consumer.key(), &token_program)?;
 
-        let cpi_context = CpiContext::new_with_signer(self.token_program.to_account_info(), accounts, &signer_seeds);
-        
-        transfer_checked(
-            cpi_context,
-            self.vault_token_account_a.amount,
-            self.token_mint_a.decimals,
-        )?;
+            let create_accounts = Create {
+                payer: self.initiator.to_account_info(),
+                associated_token: item.token_account.clone(),
+                authority: self.consumer.to_account_info(),
+                mint: item.mint.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            };
+            create_idempotent(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                create_accounts,
+            ))?;
+
+            item.release(
+                &self.token_program.to_account_info(),
+                &self.offer.to_account_info(),
+                &self.consumer.to_account_info(),
+                &signer_seeds,
+            )?;
+        }
 
         Ok(())
     }
 
-    pub fn return_sol_to_consumer(&mut self) -> Result<()> {
-        let vault_token_b = self.vault_token_account_b.as_ref().unwrap();
-        
-        if vault_token_b.amount == 0 {
-            return Ok(())
-        }
+    pub fn return_bundle_to_producer(
+        &mut self,
+        bundle_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let token_program = self.token_program.key();
+        let items = BundleItem::parse(bundle_accounts, &token_program)?;
 
-        let seeds = &[
-            b"offer",
-            self.producer.to_account_info().key.as_ref(),
-            &self.offer.id.to_le_bytes()[..],
-            &[self.offer.bump],
-        ];
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
         let signer_seeds = [&seeds[..]];
 
-        let accounts = TransferChecked {
-            from: vault_token_b.to_account_info(),
-            mint: self.token_mint_b.to_account_info(),
-            to: self.consumer_token_account_b.to_account_info(),
-            authority: self.offer.to_account_info(),
-        };
+        for (item, bundle_mint) in items.iter().zip(self.offer.bundle_mints.iter()) {
+            require_keys_eq!(
+                item.mint.key(),
+                *bundle_mint,
+                GrpxProtocolError::InvalidBundleAccounts
+            );
+            item.validate(&self.offer.key(), &self.producer.key(), &token_program)?;
 
-        let cpi_context = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
-            accounts,
-            &signer_seeds,
-        );
+            let create_accounts = Create {
+                payer: self.initiator.to_account_info(),
+                associated_token: item.token_account.clone(),
+                authority: self.producer.to_account_info(),
+                mint: item.mint.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            };
+            create_idempotent(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                create_accounts,
+            ))?;
 
-        transfer_checked(
-            cpi_context,
-            vault_token_b.amount,
-            self.token_mint_b.decimals,
-        )?;
+            item.release(
+                &self.token_program.to_account_info(),
+                &self.offer.to_account_info(),
+                &self.initiator.to_account_info(),
+                &signer_seeds,
+            )?;
+        }
 
         Ok(())
     }
 
-    pub fn close_vaults(&mut self) -> Result<()> {
-        let seeds = &[
-            b"offer",
-            self.producer.to_account_info().key.as_ref(),
-            &self.offer.id.to_le_bytes()[..],
-            &[self.offer.bump],
-        ];
-        let signer_seeds = [&seeds[..]];
+    pub fn return_payment_to_consumer(&mut self) -> Result<()> {
+        let amount = escrowed_payment(
+            &self.offer.to_account_info(),
+            self.vault_token_account_b.as_ref(),
+            &self.token_mint_b.key(),
+        )?;
+        if amount == 0 {
+            return Ok(());
+        }
 
-        let producer_accounts = CloseAccount {
-            account: self.vault_token_account_a.to_account_info(),
-            destination: self.initiator.to_account_info(),
-            authority: self.offer.to_account_info(),
+        let consumer_account = if self.offer.is_native() {
+            self.consumer.to_account_info()
+        } else {
+            self.consumer_token_account_b
+                .as_ref()
+                .ok_or(GrpxProtocolError::InvalidPaymentAccounts)?
+                .to_account_info()
         };
-        let producer_cpi_context = CpiContext::new_with_signer(
+
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
+        let signer_seeds = [&seeds[..]];
+
+        pay_from_escrow(
+            self.offer.to_account_info(),
+            self.vault_token_account_b.as_ref(),
+            &self.token_mint_b,
+            consumer_account,
+            amount,
             self.token_program.to_account_info(),
-            producer_accounts,
             &signer_seeds,
-        );
-        close_account(producer_cpi_context)?;
+        )
+    }
+
+    pub fn close_vaults(&mut self) -> Result<()> {
+        let id = self.offer.id.to_le_bytes();
+        let seeds = self.offer.signer_seeds(&id);
+
+        close_vault(
+            self.vault_token_account_a.to_account_info(),
+            self.initiator.to_account_info(),
+            self.offer.to_account_info(),
+            self.token_program.to_account_info(),
+            &[&seeds],
+        )?;
 
-        if self.offer.status == OfferStatus::Accepted {
+        if self.offer.status != OfferStatus::Created {
             if let Some(vault_token_b) = &self.vault_token_account_b {
-                let consumer_accounts = CloseAccount {
-                    account: vault_token_b.to_account_info(),
-                    destination: self.initiator.to_account_info(),
-                    authority: self.offer.to_account_info()
-                };
-
-                let consumer_cpi_context = CpiContext::new_with_signer(self.token_program.to_account_info(), consumer_accounts, &signer_seeds);
-                close_account(consumer_cpi_context)?;
+                close_vault(
+                    vault_token_b.to_account_info(),
+                    self.initiator.to_account_info(),
+                    self.offer.to_account_info(),
+                    self.token_program.to_account_info(),
+                    &[&seeds],
+                )?;
             }
         }
 
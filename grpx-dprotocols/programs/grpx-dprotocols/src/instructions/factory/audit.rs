@@ -1,3 +1,4 @@
+use crate::events::CollectionVerified;
 use anchor_lang::prelude::*;
 pub use anchor_lang::solana_program::sysvar::instructions::ID as INSTRUCTIONS_ID;
 use anchor_spl::metadata::mpl_token_metadata::instructions::{
@@ -6,6 +7,7 @@ use anchor_spl::metadata::mpl_token_metadata::instructions::{
 use anchor_spl::metadata::{MasterEditionAccount, MetadataAccount};
 use anchor_spl::{metadata::Metadata, token::Mint};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct AuditCollection<'info> {
     pub authority: Signer<'info>,
@@ -30,6 +32,14 @@ pub struct AuditCollection<'info> {
 }
 
 impl<'info> AuditCollection<'info> {
+    pub fn verified_event(&self) -> CollectionVerified {
+        CollectionVerified {
+            mint: self.mint.key(),
+            collection_mint: self.collection_mint.key(),
+            authority: self.authority.key(),
+        }
+    }
+
     pub fn verify(&mut self, bumps: &AuditCollectionBumps) -> Result<()> {
         let metadata = &self.metadata.to_account_info();
         let authority = &self.mint_authority.to_account_info();
@@ -1,3 +1,4 @@
+use crate::events::CollectionCreated;
 use crate::instructions::shared::MetadataArgs;
 use anchor_lang::prelude::*;
 use anchor_spl::metadata::mpl_token_metadata::{
@@ -13,6 +14,7 @@ use anchor_spl::{
     metadata::Metadata,
     token::{mint_to, Mint, MintTo, Token, TokenAccount},
 };
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ForgeCollection<'info> {
     #[account(mut)]
@@ -51,6 +53,17 @@ pub struct ForgeCollection<'info> {
 }
 
 impl<'info> ForgeCollection<'info> {
+    pub fn created_event(&self, metadata_args: &MetadataArgs) -> CollectionCreated {
+        CollectionCreated {
+            collection_mint: self.mint.key(),
+            owner: self.owner.key(),
+            name: metadata_args.name.clone(),
+            symbol: metadata_args.symbol.clone(),
+            uri: metadata_args.uri.clone(),
+            seller_fee_basis_points: metadata_args.seller_fee_basis_points,
+        }
+    }
+
     pub fn create(
         &mut self,
         bumps: &ForgeCollectionBumps,
@@ -79,7 +92,7 @@ impl<'info> ForgeCollection<'info> {
         msg!("Collection NFT minted!");
 
         let creator = vec![Creator {
-            address: self.mint_authority.key().clone(),
+            address: self.mint_authority.key(),
             verified: true,
             share: 100,
         }];
@@ -1,4 +1,8 @@
+use crate::constants::{ANCHOR_DISCRIMINATOR, MAX_WINE_ATTRIBUTES};
+use crate::error::GrpxProtocolError;
+use crate::events::NftMinted;
 use crate::instructions::shared::MetadataArgs;
+use crate::state::{WineAttribute, WineAttributes};
 use anchor_lang::prelude::*;
 use anchor_spl::metadata::mpl_token_metadata::{
     instructions::{
@@ -13,6 +17,7 @@ use anchor_spl::{
     metadata::Metadata,
     token::{mint_to, Mint, MintTo, Token, TokenAccount},
 };
+#[event_cpi]
 #[derive(Accounts)]
 pub struct MintNFT<'info> {
     #[account(mut)]
@@ -46,6 +51,14 @@ pub struct MintNFT<'info> {
     pub destination: Account<'info, TokenAccount>,
     #[account(mut)]
     pub collection_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + WineAttributes::INIT_SPACE,
+        seeds = [b"attributes", mint.key().as_ref()],
+        bump,
+    )]
+    pub wine_attributes: Option<Account<'info, WineAttributes>>,
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -53,6 +66,16 @@ pub struct MintNFT<'info> {
 }
 
 impl<'info> MintNFT<'info> {
+    pub fn minted_event(&self, metadata_args: &MetadataArgs) -> NftMinted {
+        NftMinted {
+            mint: self.mint.key(),
+            owner: self.owner.key(),
+            collection_mint: self.collection_mint.key(),
+            name: metadata_args.name.clone(),
+            uri: metadata_args.uri.clone(),
+        }
+    }
+
     pub fn mint(&mut self, bumps: &MintNFTBumps, metadata_args: MetadataArgs) -> Result<()> {
         let metadata = &self.metadata.to_account_info();
         let master_edition = &self.master_edition.to_account_info();
@@ -63,6 +86,13 @@ impl<'info> MintNFT<'info> {
         let spl_token_program = &self.token_program.to_account_info();
         let spl_metadata_program = &self.token_metadata_program.to_account_info();
 
+        let attributes = metadata_args.attributes.unwrap_or_default();
+        require!(
+            attributes.len() <= MAX_WINE_ATTRIBUTES
+                && attributes.iter().all(WineAttribute::is_valid),
+            GrpxProtocolError::InvalidMetadata
+        );
+
         let seeds = &[&b"authority"[..], &[bumps.mint_authority]];
         let signer_seeds = &[&seeds[..]];
 
@@ -131,6 +161,16 @@ impl<'info> MintNFT<'info> {
         );
         master_edition_account.invoke_signed(signer_seeds)?;
 
+        if let (Some(wine_attributes), Some(bump)) =
+            (&mut self.wine_attributes, bumps.wine_attributes)
+        {
+            wine_attributes.set_inner(WineAttributes {
+                mint: self.mint.key(),
+                attributes,
+                bump,
+            });
+        }
+
         Ok(())
     }
 }
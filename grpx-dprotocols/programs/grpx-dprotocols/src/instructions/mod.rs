@@ -1,7 +1,19 @@
+pub mod auctions;
+pub mod bidding;
+pub mod custody;
+pub mod delivery;
 pub mod escrow;
 pub mod factory;
+pub mod negotiation;
 pub mod shared;
+pub mod tenders;
 
+pub use auctions::*;
+pub use bidding::*;
+pub use custody::*;
+pub use delivery::*;
 pub use escrow::*;
 pub use factory::*;
+pub use negotiation::*;
 pub use shared::*;
+pub use tenders::*;
@@ -0,0 +1,146 @@
+use crate::{
+    error::GrpxProtocolError,
+    instructions::escrowed_lamports,
+    state::{CounterOffer, Offer, OfferStatus},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+#[derive(Accounts)]
+pub struct AcceptCounterOffer<'info> {
+    #[account(mut)]
+    pub producer: Signer<'info>,
+
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = producer,
+        has_one = token_mint_b,
+        constraint = offer.status == OfferStatus::Created @ GrpxProtocolError::InvalidOfferStatus,
+        constraint = !offer.is_expired(Clock::get()?.unix_timestamp) @ GrpxProtocolError::OfferExpired,
+        constraint = offer.barter.is_none() @ GrpxProtocolError::BarterNotNegotiable,
+        constraint = offer.token_a_remaining_amount == counter_offer.quantity @ GrpxProtocolError::InvalidQuantity,
+        seeds = [b"offer", producer.key().as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = offer,
+        has_one = bidder,
+        has_one = token_mint_b,
+        constraint = !counter_offer.is_expired(Clock::get()?.unix_timestamp) @ GrpxProtocolError::BidExpired,
+        seeds = [b"counter_offer", offer.key().as_ref(), bidder.key().as_ref()],
+        bump = counter_offer.bump
+    )]
+    pub counter_offer: Account<'info, CounterOffer>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = counter_offer,
+        associated_token::token_program = token_program,
+    )]
+    pub counter_vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = producer,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AcceptCounterOffer<'info> {
+    pub fn apply_counter_terms(&mut self) -> Result<()> {
+        self.offer.consumer = Some(self.bidder.key());
+        self.offer.token_b_desired_amount = self.counter_offer.token_b_proposed_amount;
+        self.offer.token_a_accepted_amount = self.counter_offer.quantity;
+        self.offer.version = self
+            .offer
+            .version
+            .checked_add(1)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
+        self.offer.status = OfferStatus::Accepted;
+
+        Ok(())
+    }
+
+    pub fn move_payment_to_offer(&mut self) -> Result<()> {
+        if self.counter_offer.is_native() {
+            require!(
+                self.vault_token_account_b.is_none(),
+                GrpxProtocolError::InvalidPaymentAccounts
+            );
+
+            let amount = escrowed_lamports(&self.counter_offer.to_account_info())?;
+            self.counter_offer.sub_lamports(amount)?;
+            self.offer.add_lamports(amount)?;
+            return Ok(());
+        }
+
+        let (Some(counter_vault_token_account_b), Some(vault_token_account_b)) = (
+            &self.counter_vault_token_account_b,
+            &self.vault_token_account_b,
+        ) else {
+            return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+        };
+
+        let offer_key = self.offer.key();
+        let seeds = &[
+            b"counter_offer",
+            offer_key.as_ref(),
+            self.counter_offer.bidder.as_ref(),
+            &[self.counter_offer.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        let accounts = TransferChecked {
+            from: counter_vault_token_account_b.to_account_info(),
+            mint: self.token_mint_b.to_account_info(),
+            to: vault_token_account_b.to_account_info(),
+            authority: self.counter_offer.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        transfer_checked(
+            cpi_context,
+            counter_vault_token_account_b.amount,
+            self.token_mint_b.decimals,
+        )?;
+
+        let accounts = CloseAccount {
+            account: counter_vault_token_account_b.to_account_info(),
+            destination: self.bidder.to_account_info(),
+            authority: self.counter_offer.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        close_account(cpi_context)
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{error::GrpxProtocolError, CounterOffer};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct RejectCounterOffer<'info> {
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    #[account(constraint = (
+        initiator.key() == bidder.key() ||
+        initiator.key() == counter_offer.producer ||
+        counter_offer.is_expired(Clock::get()?.unix_timestamp)
+    ) @ GrpxProtocolError::Unauthorized)]
+    pub initiator: Signer<'info>,
+
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        has_one = token_mint_b,
+        seeds = [b"counter_offer", counter_offer.offer.as_ref(), bidder.key().as_ref()],
+        bump = counter_offer.bump
+    )]
+    pub counter_offer: Account<'info, CounterOffer>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = counter_offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RejectCounterOffer<'info> {
+    pub fn return_payment_to_bidder(&mut self) -> Result<()> {
+        // Lamport counters are returned to the bidder with the rent when the account closes.
+        if self.counter_offer.is_native() {
+            return Ok(());
+        }
+
+        let (Some(bidder_token_account_b), Some(vault_token_account_b)) =
+            (&self.bidder_token_account_b, &self.vault_token_account_b)
+        else {
+            return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+        };
+
+        let seeds = &[
+            b"counter_offer",
+            self.counter_offer.offer.as_ref(),
+            self.bidder.to_account_info().key.as_ref(),
+            &[self.counter_offer.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        let accounts = TransferChecked {
+            from: vault_token_account_b.to_account_info(),
+            mint: self.token_mint_b.to_account_info(),
+            to: bidder_token_account_b.to_account_info(),
+            authority: self.counter_offer.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        transfer_checked(
+            cpi_context,
+            vault_token_account_b.amount,
+            self.token_mint_b.decimals,
+        )?;
+
+        let accounts = CloseAccount {
+            account: vault_token_account_b.to_account_info(),
+            destination: self.bidder.to_account_info(),
+            authority: self.counter_offer.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        close_account(cpi_context)
+    }
+}
@@ -0,0 +1,7 @@
+pub mod agree;
+pub mod decline;
+pub mod propose;
+
+pub use agree::*;
+pub use decline::*;
+pub use propose::*;
@@ -0,0 +1,131 @@
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::error::GrpxProtocolError;
+use crate::state::{CounterOffer, Offer, OfferStatus};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[derive(Accounts)]
+pub struct ProposeCounterOffer<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        has_one = token_mint_b,
+        constraint = offer.status == OfferStatus::Created @ GrpxProtocolError::InvalidOfferStatus,
+        constraint = !offer.is_expired(Clock::get()?.unix_timestamp) @ GrpxProtocolError::OfferExpired,
+        constraint = offer.barter.is_none() @ GrpxProtocolError::BarterNotNegotiable,
+        seeds = [b"offer", offer.producer.as_ref(), offer.id.to_le_bytes().as_ref()],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, Offer>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = ANCHOR_DISCRIMINATOR + CounterOffer::INIT_SPACE,
+        seeds = [b"counter_offer", offer.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub counter_offer: Account<'info, CounterOffer>,
+
+    #[account(
+        init,
+        payer = bidder,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = counter_offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ProposeCounterOffer<'info> {
+    pub fn propose(
+        &mut self,
+        token_b_proposed_amount: u64,
+        expires_at: i64,
+        proof: &[[u8; 32]],
+        bumps: &ProposeCounterOfferBumps,
+    ) -> Result<()> {
+        require!(
+            self.offer.allows(&self.bidder.key(), proof),
+            GrpxProtocolError::Unauthorized
+        );
+        require!(
+            token_b_proposed_amount > 0,
+            GrpxProtocolError::InvalidBidAmount
+        );
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            GrpxProtocolError::InvalidExpiry
+        );
+
+        self.counter_offer.set_inner(CounterOffer {
+            offer: self.offer.key(),
+            producer: self.offer.producer,
+            bidder: self.bidder.key(),
+            token_mint_b: self.token_mint_b.key(),
+            token_b_proposed_amount,
+            quantity: self.offer.token_a_remaining_amount,
+            expires_at,
+            bump: bumps.counter_offer,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_payment(&mut self) -> Result<()> {
+        let amount = self
+            .counter_offer
+            .quantity
+            .checked_mul(self.counter_offer.token_b_proposed_amount)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
+
+        if self.counter_offer.is_native() {
+            require!(
+                self.vault_token_account_b.is_none(),
+                GrpxProtocolError::InvalidPaymentAccounts
+            );
+
+            let transfer_accounts = Transfer {
+                from: self.bidder.to_account_info(),
+                to: self.counter_offer.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), transfer_accounts);
+            return transfer(cpi_ctx, amount);
+        }
+
+        let (Some(bidder_token_account_b), Some(vault_token_account_b)) =
+            (&self.bidder_token_account_b, &self.vault_token_account_b)
+        else {
+            return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+        };
+
+        let transfer_accounts = TransferChecked {
+            from: bidder_token_account_b.to_account_info(),
+            mint: self.token_mint_b.to_account_info(),
+            to: vault_token_account_b.to_account_info(),
+            authority: self.bidder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+        transfer_checked(cpi_ctx, amount, self.token_mint_b.decimals)
+    }
+}
@@ -1,4 +1,20 @@
+use crate::{
+    constants::ANCHOR_DISCRIMINATOR,
+    error::GrpxProtocolError,
+    state::{Listing, Offer, WineAttribute},
+};
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_spl::{
+    metadata::{mpl_token_metadata, MetadataAccount},
+    token::spl_token,
+    token_2022::spl_token_2022,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TransferChecked,
+    },
+};
+use solana_instructions_sysvar::get_instruction_relative;
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct MetadataArgs {
     pub name: String,
@@ -6,4 +22,245 @@ pub struct MetadataArgs {
     pub description: String,
     pub uri: String,
     pub seller_fee_basis_points: u16,
+    pub attributes: Option<Vec<WineAttribute>>,
+}
+
+/// Payments in the native mint are held as lamports by the escrow PDA itself instead of a
+/// wrapped-SOL vault.
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::ID || *mint == spl_token_2022::native_mint::ID
+}
+
+pub fn escrowed_lamports(escrow: &AccountInfo) -> Result<u64> {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(escrow.data_len());
+    Ok(escrow.lamports().saturating_sub(rent_exempt_minimum))
+}
+
+pub fn escrowed_payment(
+    escrow: &AccountInfo,
+    vault: Option<&InterfaceAccount<TokenAccount>>,
+    payment_mint: &Pubkey,
+) -> Result<u64> {
+    if is_native_mint(payment_mint) {
+        return escrowed_lamports(escrow);
+    }
+
+    vault
+        .map(|vault| vault.amount)
+        .ok_or(GrpxProtocolError::InvalidPaymentAccounts.into())
+}
+
+pub fn transfer_from_vault<'info>(
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to,
+        authority,
+    };
+    let cpi_context = CpiContext::new_with_signer(token_program, accounts, signer_seeds);
+
+    transfer_checked(cpi_context, amount, mint.decimals)
+}
+
+pub fn close_vault<'info>(
+    vault: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let accounts = CloseAccount {
+        account: vault,
+        destination,
+        authority,
+    };
+    let cpi_context = CpiContext::new_with_signer(token_program, accounts, signer_seeds);
+
+    close_account(cpi_context)
+}
+
+pub fn pay_from_escrow<'info>(
+    escrow: AccountInfo<'info>,
+    vault: Option<&InterfaceAccount<'info, TokenAccount>>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    amount: u64,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if is_native_mint(&mint.key()) {
+        escrow.sub_lamports(amount)?;
+        to.add_lamports(amount)?;
+        return Ok(());
+    }
+
+    let vault = vault.ok_or(GrpxProtocolError::InvalidPaymentAccounts)?;
+    transfer_from_vault(vault, mint, to, escrow, amount, token_program, signer_seeds)
+}
+
+pub fn remove_listing<'info>(
+    offer: &mut Account<'info, Offer>,
+    listing: Option<&Account<'info, Listing>>,
+    producer: &AccountInfo<'info>,
+) -> Result<()> {
+    if !offer.listed {
+        return Ok(());
+    }
+
+    listing
+        .ok_or(GrpxProtocolError::InvalidListing)?
+        .close(producer.clone())?;
+    offer.listed = false;
+
+    Ok(())
+}
+
+pub fn wipe_shipping_details<'info>(
+    offer: &mut Account<'info, Offer>,
+    consumer: &AccountInfo<'info>,
+) -> Result<()> {
+    if offer.shipping_details.take().is_none() {
+        return Ok(());
+    }
+
+    shrink_account(
+        &offer.to_account_info(),
+        ANCHOR_DISCRIMINATOR + Offer::INIT_SPACE,
+        consumer,
+    )
+}
+
+pub fn shrink_account(
+    account: &AccountInfo,
+    len: usize,
+    rent_receiver: &AccountInfo,
+) -> Result<()> {
+    let rent = Rent::get()?;
+    let surplus = rent
+        .minimum_balance(account.data_len())
+        .saturating_sub(rent.minimum_balance(len));
+
+    account.realloc(len, false)?;
+    account.sub_lamports(surplus)?;
+    rent_receiver.add_lamports(surplus)?;
+
+    Ok(())
+}
+
+/// Checks that the instruction right before the current one is an ed25519 program
+/// instruction verifying a single signature by `signer` over `message`.
+pub fn verify_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let instruction = get_instruction_relative(-1, instructions_sysvar)
+        .map_err(|_| GrpxProtocolError::InvalidAttestation)?;
+    require_keys_eq!(
+        instruction.program_id,
+        ed25519_program::ID,
+        GrpxProtocolError::InvalidAttestation
+    );
+
+    // Header: signature count and padding, then one block of seven u16 offsets/indices.
+    let data = &instruction.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        GrpxProtocolError::InvalidAttestation
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let (public_key_offset, message_offset, message_size) = (
+        read_u16(6) as usize,
+        read_u16(10) as usize,
+        read_u16(12) as usize,
+    );
+
+    // Every index must point at this instruction's own data.
+    require!(
+        [4, 8, 14].iter().all(|&at| read_u16(at) == u16::MAX),
+        GrpxProtocolError::InvalidAttestation
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(GrpxProtocolError::InvalidAttestation)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(GrpxProtocolError::InvalidAttestation)?;
+    require!(
+        public_key == signer.as_ref() && signed_message == message,
+        GrpxProtocolError::InvalidAttestation
+    );
+
+    Ok(())
+}
+
+/// Pays `seller_fee_basis_points` of `proceeds` to the verified creators listed in the Metaplex
+/// metadata of the sold mint, by share. `creator_accounts` holds one account per verified
+/// creator: the creator wallet for lamport payments, or the creator's token account for
+/// `payment_mint`. Mints without metadata pay nothing. Returns the total paid out.
+pub fn pay_creator_royalties<'info>(
+    metadata: &AccountInfo<'info>,
+    proceeds: u64,
+    payment_mint: Option<Pubkey>,
+    creator_accounts: &'info [AccountInfo<'info>],
+    mut pay: impl FnMut(&'info AccountInfo<'info>, u64) -> Result<()>,
+) -> Result<u64> {
+    if metadata.data_is_empty() || metadata.owner != &mpl_token_metadata::ID {
+        return Ok(0);
+    }
+
+    let metadata = MetadataAccount::try_deserialize(&mut &metadata.data.borrow()[..])?;
+    let royalty = proceeds as u128 * metadata.seller_fee_basis_points as u128 / 10_000;
+
+    let creators = match &metadata.creators {
+        Some(creators) => creators,
+        None => return Ok(0),
+    };
+
+    let mut paid = 0;
+    let mut creator_accounts = creator_accounts.iter();
+    for creator in creators.iter().filter(|creator| creator.verified) {
+        let creator_account = creator_accounts
+            .next()
+            .ok_or(GrpxProtocolError::InvalidCreatorAccount)?;
+
+        match payment_mint {
+            Some(payment_mint) => {
+                let creator_token_account =
+                    InterfaceAccount::<TokenAccount>::try_from(creator_account)?;
+                require_keys_eq!(
+                    creator_token_account.owner,
+                    creator.address,
+                    GrpxProtocolError::InvalidCreatorAccount
+                );
+                require_keys_eq!(
+                    creator_token_account.mint,
+                    payment_mint,
+                    GrpxProtocolError::InvalidCreatorAccount
+                );
+            }
+            None => require_keys_eq!(
+                creator_account.key(),
+                creator.address,
+                GrpxProtocolError::InvalidCreatorAccount
+            ),
+        }
+
+        let amount = (royalty * creator.share as u128 / 100) as u64;
+        if amount > 0 {
+            pay(creator_account, amount)?;
+            paid += amount;
+        }
+    }
+
+    Ok(paid)
 }
@@ -0,0 +1,98 @@
+use crate::{error::GrpxProtocolError, state::Tender};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct CancelTender<'info> {
+    #[account(mut)]
+    pub producer: Signer<'info>,
+
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = producer,
+        associated_token::token_program = token_program,
+    )]
+    pub producer_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = producer,
+        has_one = token_mint_a,
+        constraint = !tender.settled @ GrpxProtocolError::OfferAlreadySettled,
+        constraint = (
+            tender.commitments == 0 ||
+            (tender.has_ended(Clock::get()?.unix_timestamp) && tender.highest_bidder.is_none())
+        ) @ GrpxProtocolError::InvalidTenderPhase,
+        seeds = [b"tender", producer.key().as_ref(), tender.id.to_le_bytes().as_ref()],
+        bump = tender.bump
+    )]
+    pub tender: Account<'info, Tender>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = tender,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelTender<'info> {
+    pub fn return_nft_to_producer(&mut self) -> Result<()> {
+        let seeds = &[
+            b"tender",
+            self.producer.to_account_info().key.as_ref(),
+            &self.tender.id.to_le_bytes()[..],
+            &[self.tender.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        let accounts = TransferChecked {
+            from: self.vault_token_account_a.to_account_info(),
+            mint: self.token_mint_a.to_account_info(),
+            to: self.producer_token_account_a.to_account_info(),
+            authority: self.tender.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        transfer_checked(
+            cpi_context,
+            self.vault_token_account_a.amount,
+            self.token_mint_a.decimals,
+        )?;
+
+        let accounts = CloseAccount {
+            account: self.vault_token_account_a.to_account_info(),
+            destination: self.producer.to_account_info(),
+            authority: self.tender.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        close_account(cpi_context)
+    }
+
+    pub fn close_tender_if_released(&mut self) -> Result<()> {
+        self.tender.settled = true;
+
+        if self.tender.commitments == 0 {
+            self.tender.close(self.producer.to_account_info())?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::error::GrpxProtocolError;
+use crate::state::{Tender, TenderPricing};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TenderArgs {
+    pub reserve_price: u64,
+    pub deposit: u64,
+    pub pricing: TenderPricing,
+    pub commit_ends_at: i64,
+    pub reveal_ends_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateTender<'info> {
+    #[account(mut)]
+    pub producer: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = token_mint_a.supply == 1 && token_mint_a.decimals == 0 @ GrpxProtocolError::InvalidNftMint,
+    )]
+    pub token_mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = producer,
+        associated_token::token_program = token_program
+    )]
+    pub producer_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = producer,
+        space = ANCHOR_DISCRIMINATOR + Tender::INIT_SPACE,
+        seeds = [b"tender", producer.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tender: Account<'info, Tender>,
+
+    #[account(
+        init,
+        payer = producer,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = tender,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_a: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateTender<'info> {
+    pub fn open_tender(
+        &mut self,
+        id: u64,
+        tender_args: TenderArgs,
+        bumps: &CreateTenderBumps,
+    ) -> Result<()> {
+        let TenderArgs {
+            reserve_price,
+            deposit,
+            pricing,
+            commit_ends_at,
+            reveal_ends_at,
+        } = tender_args;
+
+        require!(
+            reserve_price > 0 && deposit > 0,
+            GrpxProtocolError::InvalidBidAmount
+        );
+        require!(
+            commit_ends_at > Clock::get()?.unix_timestamp && reveal_ends_at > commit_ends_at,
+            GrpxProtocolError::InvalidExpiry
+        );
+
+        self.tender.set_inner(Tender {
+            id,
+            producer: self.producer.key(),
+            token_mint_a: self.token_mint_a.key(),
+            token_mint_b: self.token_mint_b.key(),
+            reserve_price,
+            deposit,
+            pricing,
+            commit_ends_at,
+            reveal_ends_at,
+            commitments: 0,
+            highest_bidder: None,
+            highest_bid: 0,
+            second_bid: 0,
+            settled: false,
+            bump: bumps.tender,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_nft_to_vault(&mut self) -> Result<()> {
+        let transfer_accounts = TransferChecked {
+            from: self.producer_token_account_a.to_account_info(),
+            mint: self.token_mint_a.to_account_info(),
+            to: self.vault_token_account_a.to_account_info(),
+            authority: self.producer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+
+        transfer_checked(cpi_ctx, 1, self.token_mint_a.decimals)
+    }
+}
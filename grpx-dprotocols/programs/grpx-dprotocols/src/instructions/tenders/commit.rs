@@ -0,0 +1,108 @@
+use crate::constants::ANCHOR_DISCRIMINATOR;
+use crate::error::GrpxProtocolError;
+use crate::state::{SealedBid, Tender};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[derive(Accounts)]
+pub struct CommitSealedBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = token_mint_b,
+        constraint = tender.is_committing(Clock::get()?.unix_timestamp) @ GrpxProtocolError::InvalidTenderPhase,
+        seeds = [b"tender", tender.producer.as_ref(), tender.id.to_le_bytes().as_ref()],
+        bump = tender.bump
+    )]
+    pub tender: Account<'info, Tender>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = ANCHOR_DISCRIMINATOR + SealedBid::INIT_SPACE,
+        seeds = [b"sealed_bid", tender.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+
+    #[account(
+        init,
+        payer = bidder,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = sealed_bid,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CommitSealedBid<'info> {
+    pub fn commit_bid(&mut self, commitment: [u8; 32], bumps: &CommitSealedBidBumps) -> Result<()> {
+        self.sealed_bid.set_inner(SealedBid {
+            tender: self.tender.key(),
+            bidder: self.bidder.key(),
+            commitment,
+            revealed_amount: None,
+            bump: bumps.sealed_bid,
+        });
+
+        self.tender.commitments = self
+            .tender
+            .commitments
+            .checked_add(1)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn deposit_collateral(&mut self) -> Result<()> {
+        if self.tender.is_native() {
+            require!(
+                self.vault_token_account_b.is_none(),
+                GrpxProtocolError::InvalidPaymentAccounts
+            );
+
+            let transfer_accounts = Transfer {
+                from: self.bidder.to_account_info(),
+                to: self.sealed_bid.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), transfer_accounts);
+            return transfer(cpi_ctx, self.tender.deposit);
+        }
+
+        let (Some(bidder_token_account_b), Some(vault_token_account_b)) =
+            (&self.bidder_token_account_b, &self.vault_token_account_b)
+        else {
+            return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+        };
+
+        let transfer_accounts = TransferChecked {
+            from: bidder_token_account_b.to_account_info(),
+            mint: self.token_mint_b.to_account_info(),
+            to: vault_token_account_b.to_account_info(),
+            authority: self.bidder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+        transfer_checked(cpi_ctx, self.tender.deposit, self.token_mint_b.decimals)
+    }
+}
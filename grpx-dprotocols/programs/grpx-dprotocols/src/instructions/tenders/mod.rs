@@ -0,0 +1,13 @@
+pub mod abandon;
+pub mod announce;
+pub mod award;
+pub mod commit;
+pub mod release;
+pub mod reveal;
+
+pub use abandon::*;
+pub use announce::*;
+pub use award::*;
+pub use commit::*;
+pub use release::*;
+pub use reveal::*;
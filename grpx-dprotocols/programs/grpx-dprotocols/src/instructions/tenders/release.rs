@@ -0,0 +1,182 @@
+use crate::{
+    error::GrpxProtocolError,
+    instructions::transfer_from_vault,
+    state::{SealedBid, Tender},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, CloseAccount, Mint, TokenAccount, TokenInterface,
+};
+
+#[derive(Accounts)]
+pub struct ReleaseSealedBid<'info> {
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub producer: SystemAccount<'info>,
+
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = producer,
+        associated_token::token_program = token_program,
+    )]
+    pub producer_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = producer,
+        has_one = token_mint_b,
+        constraint = tender.has_ended(Clock::get()?.unix_timestamp) @ GrpxProtocolError::InvalidTenderPhase,
+        seeds = [b"tender", producer.key().as_ref(), tender.id.to_le_bytes().as_ref()],
+        bump = tender.bump
+    )]
+    pub tender: Account<'info, Tender>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        has_one = tender,
+        seeds = [b"sealed_bid", tender.key().as_ref(), bidder.key().as_ref()],
+        bump = sealed_bid.bump
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = sealed_bid,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ReleaseSealedBid<'info> {
+    pub fn check_payment_accounts(&self) -> Result<()> {
+        require!(
+            self.tender.is_native() || self.vault_token_account_b.is_some(),
+            GrpxProtocolError::InvalidPaymentAccounts
+        );
+
+        Ok(())
+    }
+
+    pub fn forfeit_unrevealed_deposit(&mut self) -> Result<()> {
+        if self.sealed_bid.revealed_amount.is_some() {
+            require!(
+                self.tender.settled || self.tender.highest_bidder != Some(self.bidder.key()),
+                GrpxProtocolError::TenderNotSettled
+            );
+            return Ok(());
+        }
+
+        let deposit = self.tender.deposit;
+
+        if self.tender.is_native() {
+            self.sealed_bid.sub_lamports(deposit)?;
+            self.producer.add_lamports(deposit)?;
+            return Ok(());
+        }
+
+        let producer_token_account_b = self
+            .producer_token_account_b
+            .as_ref()
+            .ok_or(GrpxProtocolError::InvalidPaymentAccounts)?
+            .to_account_info();
+        self.transfer_from_vault(producer_token_account_b, deposit)
+    }
+
+    pub fn return_escrow_to_bidder(&mut self) -> Result<()> {
+        let Some(vault_token_account_b) = &self.vault_token_account_b else {
+            return Ok(());
+        };
+
+        let mut vault_token_account_b = vault_token_account_b.clone();
+        vault_token_account_b.reload()?;
+
+        if vault_token_account_b.amount > 0 {
+            let bidder_token_account_b = self
+                .bidder_token_account_b
+                .as_ref()
+                .ok_or(GrpxProtocolError::InvalidPaymentAccounts)?
+                .to_account_info();
+            self.transfer_from_vault(bidder_token_account_b, vault_token_account_b.amount)?;
+        }
+
+        let tender_key = self.tender.key();
+        let seeds = &[
+            b"sealed_bid",
+            tender_key.as_ref(),
+            self.sealed_bid.bidder.as_ref(),
+            &[self.sealed_bid.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        let accounts = CloseAccount {
+            account: vault_token_account_b.to_account_info(),
+            destination: self.bidder.to_account_info(),
+            authority: self.sealed_bid.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        close_account(cpi_context)
+    }
+
+    pub fn close_tender_if_released(&mut self) -> Result<()> {
+        self.tender.commitments = self
+            .tender
+            .commitments
+            .checked_sub(1)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
+
+        if self.tender.settled && self.tender.commitments == 0 {
+            self.tender.close(self.producer.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    fn transfer_from_vault(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let vault_token_account_b = self
+            .vault_token_account_b
+            .as_ref()
+            .ok_or(GrpxProtocolError::InvalidPaymentAccounts)?;
+
+        let tender_key = self.tender.key();
+        let seeds = &[
+            b"sealed_bid",
+            tender_key.as_ref(),
+            self.sealed_bid.bidder.as_ref(),
+            &[self.sealed_bid.bump],
+        ];
+        let signer_seeds = [&seeds[..]];
+
+        transfer_from_vault(
+            vault_token_account_b,
+            &self.token_mint_b,
+            to,
+            self.sealed_bid.to_account_info(),
+            amount,
+            self.token_program.to_account_info(),
+            &signer_seeds,
+        )
+    }
+}
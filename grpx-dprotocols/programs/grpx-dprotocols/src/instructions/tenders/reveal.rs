@@ -0,0 +1,100 @@
+use crate::error::GrpxProtocolError;
+use crate::state::{SealedBid, Tender};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[derive(Accounts)]
+pub struct RevealSealedBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = token_mint_b,
+        constraint = tender.is_revealing(Clock::get()?.unix_timestamp) @ GrpxProtocolError::InvalidTenderPhase,
+        seeds = [b"tender", tender.producer.as_ref(), tender.id.to_le_bytes().as_ref()],
+        bump = tender.bump
+    )]
+    pub tender: Account<'info, Tender>,
+
+    #[account(
+        mut,
+        has_one = bidder,
+        has_one = tender,
+        constraint = sealed_bid.revealed_amount.is_none() @ GrpxProtocolError::InvalidReveal,
+        seeds = [b"sealed_bid", tender.key().as_ref(), bidder.key().as_ref()],
+        bump = sealed_bid.bump
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = sealed_bid,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account_b: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RevealSealedBid<'info> {
+    pub fn reveal_bid(&mut self, amount: u64, salt: [u8; 32]) -> Result<()> {
+        require!(
+            self.sealed_bid.matches(amount, &salt),
+            GrpxProtocolError::InvalidReveal
+        );
+
+        self.sealed_bid.revealed_amount = Some(amount);
+
+        if amount < self.tender.reserve_price {
+            return Ok(());
+        }
+
+        self.deposit_bid(amount)?;
+        self.tender.record_bid(self.bidder.key(), amount);
+
+        Ok(())
+    }
+
+    fn deposit_bid(&self, amount: u64) -> Result<()> {
+        if self.tender.is_native() {
+            let transfer_accounts = Transfer {
+                from: self.bidder.to_account_info(),
+                to: self.sealed_bid.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), transfer_accounts);
+            return transfer(cpi_ctx, amount);
+        }
+
+        let (Some(bidder_token_account_b), Some(vault_token_account_b)) =
+            (&self.bidder_token_account_b, &self.vault_token_account_b)
+        else {
+            return Err(GrpxProtocolError::InvalidPaymentAccounts.into());
+        };
+
+        let transfer_accounts = TransferChecked {
+            from: bidder_token_account_b.to_account_info(),
+            mint: self.token_mint_b.to_account_info(),
+            to: vault_token_account_b.to_account_info(),
+            authority: self.bidder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+        transfer_checked(cpi_ctx, amount, self.token_mint_b.decimals)
+    }
+}
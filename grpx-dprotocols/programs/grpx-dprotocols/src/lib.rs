@@ -2,6 +2,7 @@
 
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
@@ -9,6 +10,7 @@ use anchor_lang::prelude::*;
 
 pub use constants::*;
 pub use error::*;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 
@@ -19,48 +21,430 @@ pub mod grpx_dprotocols {
 
     // === Factory Instructions ===
     pub fn create(ctx: Context<ForgeCollection>, metadata_args: MetadataArgs) -> Result<()> {
-        ctx.accounts.create(&ctx.bumps, metadata_args)
+        let event = ctx.accounts.created_event(&metadata_args);
+        ctx.accounts.create(&ctx.bumps, metadata_args)?;
+        emit_cpi!(event);
+
+        Ok(())
     }
 
     pub fn mint(ctx: Context<MintNFT>, metadata_args: MetadataArgs) -> Result<()> {
-        ctx.accounts.mint(&ctx.bumps, metadata_args)
+        let event = ctx.accounts.minted_event(&metadata_args);
+        ctx.accounts.mint(&ctx.bumps, metadata_args)?;
+        emit_cpi!(event);
+
+        Ok(())
     }
 
     pub fn verify(ctx: Context<AuditCollection>) -> Result<()> {
-        ctx.accounts.verify(&ctx.bumps)
+        ctx.accounts.verify(&ctx.bumps)?;
+        emit_cpi!(ctx.accounts.verified_event());
+
+        Ok(())
     }
 
     // === Escrow Instructions ===
-    pub fn open(
-        ctx: Context<CreateOffer>,
-        id: u64,
+    pub fn open<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateOffer<'info>>,
         token_a_offered_amount: u64,
         token_b_desired_amount: u64,
+        allowlist: Option<OfferAllowlist>,
+        expires_at: Option<i64>,
     ) -> Result<()> {
-        ctx.accounts
-            .open_vault(id, token_b_desired_amount, &ctx.bumps)?;
+        ctx.accounts.open_vault(
+            token_a_offered_amount,
+            token_b_desired_amount,
+            allowlist,
+            expires_at,
+            &ctx.bumps,
+        )?;
         ctx.accounts.deposit_nft_to_vault(token_a_offered_amount)?;
+        ctx.accounts
+            .deposit_bundle_to_vaults(ctx.remaining_accounts)?;
+        emit_cpi!(ctx.accounts.opened_event());
+
+        Ok(())
+    }
+
+    pub fn accept<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptOffer<'info>>,
+        quantity: u64,
+        proof: Vec<[u8; 32]>,
+        expected_version: u32,
+        delivery_code_hash: Option<[u8; 32]>,
+        shipping_details: Option<ShippingDetails>,
+    ) -> Result<()> {
+        let consumer = ctx.accounts.consumer.key();
+        ctx.accounts.offer.check_terms(expected_version)?;
+        ctx.accounts.offer.check_allowlist(&consumer, &proof)?;
+        ctx.accounts.set_delivery_code(delivery_code_hash)?;
+        ctx.accounts.attach_shipping_details(shipping_details)?;
+        ctx.accounts.deposit_payment(quantity)?;
+        ctx.accounts.deposit_barter(ctx.remaining_accounts)?;
+        emit_cpi!(ctx.accounts.accepted_event()?);
+
+        Ok(())
+    }
+
+    pub fn amend(ctx: Context<AmendOffer>, amend_args: AmendArgs) -> Result<()> {
+        ctx.accounts.amend_terms(amend_args, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn delist(ctx: Context<DelistOffer>) -> Result<()> {
+        ctx.accounts.delist()?;
+
+        Ok(())
+    }
+
+    pub fn confirm<'info>(ctx: Context<'_, '_, 'info, 'info, ConfirmOffer<'info>>) -> Result<()> {
+        let event = ctx.accounts.settle(&ctx.bumps, ctx.remaining_accounts)?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    pub fn refund<'info>(ctx: Context<'_, '_, 'info, 'info, RefundOffer<'info>>) -> Result<()> {
+        let event = ctx.accounts.refund(ctx.remaining_accounts)?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    pub fn buy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuyOffer<'info>>,
+        quantity: u64,
+        proof: Vec<[u8; 32]>,
+        expected_version: u32,
+    ) -> Result<()> {
+        let (bundle_accounts, remaining_accounts) = ctx
+            .accounts
+            .offer
+            .split_bundle_accounts(ctx.remaining_accounts)?;
+        let (barter_accounts, creator_accounts) = ctx
+            .accounts
+            .offer
+            .split_barter_deposit_accounts(remaining_accounts)?;
+
+        let consumer = ctx.accounts.consumer.key();
+        ctx.accounts.offer.check_terms(expected_version)?;
+        ctx.accounts.offer.check_allowlist(&consumer, &proof)?;
+        ctx.accounts.offer.check_quantity(quantity)?;
+        ctx.accounts.record_receipt(quantity, &ctx.bumps)?;
+        ctx.accounts
+            .transfer_barter_to_producer(quantity, barter_accounts)?;
+        ctx.accounts.pay_producer(quantity, creator_accounts)?;
+        ctx.accounts.transfer_nft_to_consumer(quantity)?;
+
+        if ctx.accounts.offer.is_filled() {
+            ctx.accounts.transfer_bundle_to_consumer(bundle_accounts)?;
+            remove_listing(
+                &mut ctx.accounts.offer,
+                ctx.accounts.listing.as_ref(),
+                &ctx.accounts.producer.to_account_info(),
+            )?;
+            ctx.accounts.close_vault()?;
+        }
+        emit_cpi!(ctx.accounts.bought_event());
+
+        Ok(())
+    }
+
+    pub fn confirm_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleMany<'info>>,
+        extra_account_counts: Vec<u8>,
+    ) -> Result<()> {
+        let mut accounts = ctx.remaining_accounts;
+
+        for extra_account_count in extra_account_counts {
+            let (mut entry, bumps, extra_accounts) = SettleMany::next_entry::<ConfirmOffer>(
+                ctx.program_id,
+                &mut accounts,
+                extra_account_count,
+            )?;
+            let event = entry.settle(&bumps, extra_accounts)?;
+            entry.exit(ctx.program_id)?;
+            emit_cpi!(event);
+        }
+
+        SettleMany::check_consumed(accounts)
+    }
+
+    pub fn refund_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleMany<'info>>,
+        extra_account_counts: Vec<u8>,
+    ) -> Result<()> {
+        let mut accounts = ctx.remaining_accounts;
+
+        for extra_account_count in extra_account_counts {
+            let (mut entry, _, extra_accounts) = SettleMany::next_entry::<RefundOffer>(
+                ctx.program_id,
+                &mut accounts,
+                extra_account_count,
+            )?;
+            let event = entry.refund(extra_accounts)?;
+            entry.exit(ctx.program_id)?;
+            emit_cpi!(event);
+        }
+
+        SettleMany::check_consumed(accounts)
+    }
+
+    // === Delivery Instructions ===
+    pub fn ship(
+        ctx: Context<ShipOffer>,
+        tracking_hash: Option<[u8; 32]>,
+        carrier: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.mark_shipped(tracking_hash, carrier)?;
+
+        Ok(())
+    }
+
+    pub fn update_delivery(
+        ctx: Context<UpdateDelivery>,
+        milestone: DeliveryMilestone,
+        tracking_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.advance(milestone, tracking_hash)?;
+
+        Ok(())
+    }
+
+    pub fn attest_delivery<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AttestDelivery<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.record_delivery()?;
+        ctx.accounts
+            .release_shipment_share(ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    pub fn report_breach(ctx: Context<ReportBreach>, report: BreachReport) -> Result<()> {
+        ctx.accounts.record_breach(report)?;
+
+        Ok(())
+    }
+
+    pub fn reveal_delivery_code(ctx: Context<RevealDeliveryCode>, code: Vec<u8>) -> Result<()> {
+        ctx.accounts.record_handover(&code)?;
+
+        Ok(())
+    }
+
+    pub fn verify_location(ctx: Context<VerifyLocation>, geohash: String) -> Result<()> {
+        ctx.accounts.record_location(&geohash)?;
+
+        Ok(())
+    }
+
+    pub fn dispute_delivery(ctx: Context<DisputeDelivery>) -> Result<()> {
+        ctx.accounts.open_dispute()?;
+
+        Ok(())
+    }
+
+    // === Custody Instructions ===
+    pub fn register_custody(ctx: Context<RegisterCustody>) -> Result<()> {
+        ctx.accounts.register(&ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn handoff(ctx: Context<Handoff>, location_hash: Option<[u8; 32]>) -> Result<()> {
+        ctx.accounts.record_handoff(location_hash)?;
+
+        Ok(())
+    }
+
+    // === Negotiation Instructions ===
+    pub fn propose_counter_offer(
+        ctx: Context<ProposeCounterOffer>,
+        token_b_proposed_amount: u64,
+        expires_at: i64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts
+            .propose(token_b_proposed_amount, expires_at, &proof, &ctx.bumps)?;
+        ctx.accounts.deposit_payment()?;
+
+        Ok(())
+    }
+
+    pub fn accept_counter_offer(ctx: Context<AcceptCounterOffer>) -> Result<()> {
+        ctx.accounts.apply_counter_terms()?;
+        ctx.accounts.move_payment_to_offer()?;
+
+        Ok(())
+    }
+
+    pub fn reject_counter_offer(ctx: Context<RejectCounterOffer>) -> Result<()> {
+        ctx.accounts.return_payment_to_bidder()?;
+
+        Ok(())
+    }
+
+    // === Bid Instructions ===
+    pub fn place_bid(
+        ctx: Context<PlaceBid>,
+        token_b_offered_amount: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .place_bid(token_b_offered_amount, expires_at, &ctx.bumps)?;
+        ctx.accounts.deposit_payment()?;
+
+        Ok(())
+    }
+
+    pub fn accept_bid<'info>(ctx: Context<'_, '_, 'info, 'info, AcceptBid<'info>>) -> Result<()> {
+        ctx.accounts.pay_creator_royalties(ctx.remaining_accounts)?;
+        ctx.accounts.transfer_payment_to_holder()?;
+        ctx.accounts.transfer_nft_to_bidder()?;
+        ctx.accounts.close_vault()?;
+
+        Ok(())
+    }
+
+    pub fn accept_bid_for_delivery(ctx: Context<AcceptBidForDelivery>) -> Result<()> {
+        ctx.accounts.open_accepted_offer(&ctx.bumps)?;
+        ctx.accounts.deposit_nft_to_vault()?;
+        ctx.accounts.move_payment_to_offer()?;
+
+        Ok(())
+    }
+
+    pub fn cancel_bid(ctx: Context<CancelBid>) -> Result<()> {
+        ctx.accounts.return_payment_to_bidder()?;
+
+        Ok(())
+    }
+
+    pub fn place_collection_bid(
+        ctx: Context<PlaceCollectionBid>,
+        id: u64,
+        token_b_offered_amount: u64,
+        quantity: u64,
+        required_attribute: Option<WineAttribute>,
+        expires_at: i64,
+    ) -> Result<()> {
+        ctx.accounts.place_collection_bid(
+            id,
+            token_b_offered_amount,
+            quantity,
+            required_attribute,
+            expires_at,
+            &ctx.bumps,
+        )?;
+        ctx.accounts.deposit_payment()?;
+
+        Ok(())
+    }
+
+    pub fn fill_collection_bid<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FillCollectionBid<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.check_required_attribute()?;
+        ctx.accounts.pay_holder(ctx.remaining_accounts)?;
+        ctx.accounts.transfer_nft_to_bidder()?;
+        ctx.accounts.close_if_filled()?;
+
+        Ok(())
+    }
+
+    pub fn cancel_collection_bid(ctx: Context<CancelCollectionBid>) -> Result<()> {
+        ctx.accounts.return_payment_to_bidder()?;
+
+        Ok(())
+    }
+
+    // === Auction Instructions ===
+
+    pub fn start_auction(
+        ctx: Context<CreateAuction>,
+        id: u64,
+        reserve_price: u64,
+        min_increment: u64,
+        ends_at: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .start_auction(id, reserve_price, min_increment, ends_at, &ctx.bumps)?;
+        ctx.accounts.deposit_nft_to_vault()?;
+
+        Ok(())
+    }
+
+    pub fn place_auction_bid(ctx: Context<PlaceAuctionBid>, amount: u64) -> Result<()> {
+        ctx.accounts.check_bid_amount(amount)?;
+        ctx.accounts.refund_previous_bidder()?;
+        ctx.accounts.deposit_bid(amount)?;
+
+        Ok(())
+    }
+
+    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+        ctx.accounts.open_accepted_offer(&ctx.bumps)?;
+        ctx.accounts.move_nft_to_offer()?;
+        ctx.accounts.move_payment_to_offer()?;
+
+        Ok(())
+    }
+
+    pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
+        ctx.accounts.return_nft_to_producer()?;
+        ctx.accounts.close_payment_vault()?;
+
+        Ok(())
+    }
+
+    // === Tender Instructions ===
+
+    pub fn open_tender(ctx: Context<CreateTender>, id: u64, tender_args: TenderArgs) -> Result<()> {
+        ctx.accounts.open_tender(id, tender_args, &ctx.bumps)?;
+        ctx.accounts.deposit_nft_to_vault()?;
+
+        Ok(())
+    }
+
+    pub fn commit_sealed_bid(ctx: Context<CommitSealedBid>, commitment: [u8; 32]) -> Result<()> {
+        ctx.accounts.commit_bid(commitment, &ctx.bumps)?;
+        ctx.accounts.deposit_collateral()?;
+
+        Ok(())
+    }
+
+    pub fn reveal_sealed_bid(
+        ctx: Context<RevealSealedBid>,
+        amount: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.reveal_bid(amount, salt)?;
 
         Ok(())
     }
 
-    pub fn accept(ctx: Context<AcceptOffer>) -> Result<()> {
-        ctx.accounts.deposit_sol_to_vault()?;
+    pub fn settle_tender(ctx: Context<SettleTender>) -> Result<()> {
+        ctx.accounts.open_accepted_offer(&ctx.bumps)?;
+        ctx.accounts.move_nft_to_offer()?;
+        ctx.accounts.move_payment_to_offer()?;
 
         Ok(())
     }
 
-    pub fn confirm(ctx: Context<ConfirmOffer>) -> Result<()> {
-        ctx.accounts.transfer_sol_to_producer()?;
-        ctx.accounts.transfer_nft_to_consumer()?;
-        ctx.accounts.close_vaults()?;
+    pub fn release_sealed_bid(ctx: Context<ReleaseSealedBid>) -> Result<()> {
+        ctx.accounts.check_payment_accounts()?;
+        ctx.accounts.forfeit_unrevealed_deposit()?;
+        ctx.accounts.return_escrow_to_bidder()?;
+        ctx.accounts.close_tender_if_released()?;
 
         Ok(())
     }
 
-    pub fn refund(ctx: Context<RefundOffer>) -> Result<()> {
-        ctx.accounts.process_refund()?;
-        ctx.accounts.close_vaults()?;
+    pub fn cancel_tender(ctx: Context<CancelTender>) -> Result<()> {
+        ctx.accounts.return_nft_to_producer()?;
+        ctx.accounts.close_tender_if_released()?;
 
         Ok(())
     }
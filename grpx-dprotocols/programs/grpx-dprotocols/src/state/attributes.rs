@@ -0,0 +1,33 @@
+use crate::constants::{MAX_ATTRIBUTE_LEN, MAX_WINE_ATTRIBUTES};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct WineAttribute {
+    #[max_len(MAX_ATTRIBUTE_LEN)]
+    pub trait_type: String,
+    #[max_len(MAX_ATTRIBUTE_LEN)]
+    pub value: String,
+}
+
+impl WineAttribute {
+    pub fn is_valid(&self) -> bool {
+        !self.trait_type.is_empty()
+            && self.trait_type.len() <= MAX_ATTRIBUTE_LEN
+            && self.value.len() <= MAX_ATTRIBUTE_LEN
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct WineAttributes {
+    pub mint: Pubkey,
+    #[max_len(MAX_WINE_ATTRIBUTES)]
+    pub attributes: Vec<WineAttribute>,
+    pub bump: u8,
+}
+
+impl WineAttributes {
+    pub fn contains(&self, attribute: &WineAttribute) -> bool {
+        self.attributes.contains(attribute)
+    }
+}
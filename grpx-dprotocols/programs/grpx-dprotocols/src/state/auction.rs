@@ -0,0 +1,45 @@
+use crate::constants::AUCTION_EXTENSION_SECONDS;
+use crate::error::GrpxProtocolError;
+use crate::instructions::is_native_mint;
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Auction {
+    pub id: u64,
+    pub producer: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub reserve_price: u64,
+    pub min_increment: u64,
+    pub ends_at: i64,
+    pub highest_bidder: Option<Pubkey>,
+    pub highest_bid: u64,
+    pub bump: u8,
+}
+
+impl Auction {
+    pub fn is_native(&self) -> bool {
+        is_native_mint(&self.token_mint_b)
+    }
+
+    pub fn has_ended(&self, now: i64) -> bool {
+        now >= self.ends_at
+    }
+
+    pub fn minimum_bid(&self) -> Result<u64> {
+        if self.highest_bidder.is_none() {
+            return Ok(self.reserve_price);
+        }
+
+        self.highest_bid
+            .checked_add(self.min_increment)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow.into())
+    }
+
+    pub fn extend_if_closing(&mut self, now: i64) {
+        if self.ends_at - now < AUCTION_EXTENSION_SECONDS {
+            self.ends_at = now + AUCTION_EXTENSION_SECONDS;
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::constants::{BARTER_PICK_ACCOUNTS, BUNDLE_ACCOUNTS_PER_MINT, MAX_BARTER_MINTS};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct BarterTerms {
+    #[max_len(MAX_BARTER_MINTS)]
+    pub mints: Vec<Pubkey>,
+    pub collection: Option<Pubkey>,
+    pub collection_count: u8,
+}
+
+impl BarterTerms {
+    pub fn is_valid(&self) -> bool {
+        let unique_mints = self
+            .mints
+            .iter()
+            .enumerate()
+            .all(|(i, mint)| !self.mints[..i].contains(mint));
+
+        self.wanted_count() > 0
+            && self.wanted_count() <= MAX_BARTER_MINTS
+            && unique_mints
+            && self.collection.is_some() == (self.collection_count > 0)
+    }
+
+    pub fn wanted_count(&self) -> usize {
+        self.mints.len() + self.collection_count as usize
+    }
+
+    pub fn account_count(&self) -> usize {
+        self.mints.len() * BUNDLE_ACCOUNTS_PER_MINT
+            + self.collection_count as usize * BARTER_PICK_ACCOUNTS
+    }
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// Temperatures are in tenths of a degree Celsius.
+#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq, InitSpace)]
+pub struct ColdChainTerms {
+    pub sensor: Pubkey,
+    pub min_temperature: i16,
+    pub max_temperature: i16,
+    pub penalty_bps_per_excursion: u16,
+    pub max_penalty_bps: u16,
+}
+
+impl ColdChainTerms {
+    pub fn is_valid(&self) -> bool {
+        self.min_temperature <= self.max_temperature && self.max_penalty_bps <= 10_000
+    }
+
+    pub fn penalty_bps(&self, min_recorded: i16, max_recorded: i16, excursions: u16) -> u16 {
+        if min_recorded >= self.min_temperature && max_recorded <= self.max_temperature {
+            return 0;
+        }
+
+        (excursions as u32 * self.penalty_bps_per_excursion as u32).min(self.max_penalty_bps as u32)
+            as u16
+    }
+}
@@ -0,0 +1,27 @@
+use crate::instructions::is_native_mint;
+use crate::state::WineAttribute;
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct CollectionBid {
+    pub id: u64,
+    pub bidder: Pubkey,
+    pub collection_mint: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_b_offered_amount: u64,
+    pub quantity_remaining: u64,
+    pub required_attribute: Option<WineAttribute>,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl CollectionBid {
+    pub fn is_native(&self) -> bool {
+        is_native_mint(&self.token_mint_b)
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now > self.expires_at
+    }
+}
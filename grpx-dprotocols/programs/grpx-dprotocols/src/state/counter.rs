@@ -0,0 +1,24 @@
+use crate::error::GrpxProtocolError;
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProducerCounter {
+    pub producer: Pubkey,
+    pub offer_count: u64,
+    pub bump: u8,
+}
+
+impl ProducerCounter {
+    pub fn assign_offer_id(&mut self, producer: Pubkey, bump: u8) -> Result<u64> {
+        let id = self.offer_count;
+
+        self.producer = producer;
+        self.offer_count = id
+            .checked_add(1)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow)?;
+        self.bump = bump;
+
+        Ok(id)
+    }
+}
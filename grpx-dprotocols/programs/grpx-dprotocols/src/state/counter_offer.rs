@@ -0,0 +1,25 @@
+use crate::instructions::is_native_mint;
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct CounterOffer {
+    pub offer: Pubkey,
+    pub producer: Pubkey,
+    pub bidder: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_b_proposed_amount: u64,
+    pub quantity: u64,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl CounterOffer {
+    pub fn is_native(&self) -> bool {
+        is_native_mint(&self.token_mint_b)
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now > self.expires_at
+    }
+}
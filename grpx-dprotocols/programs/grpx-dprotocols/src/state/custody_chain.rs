@@ -0,0 +1,20 @@
+use crate::constants::MAX_CUSTODY_HOPS;
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct CustodyHop {
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub location_hash: Option<[u8; 32]>,
+    pub timestamp: i64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CustodyChain {
+    pub mint: Pubkey,
+    pub custodian: Pubkey,
+    #[max_len(MAX_CUSTODY_HOPS)]
+    pub hops: Vec<CustodyHop>,
+    pub bump: u8,
+}
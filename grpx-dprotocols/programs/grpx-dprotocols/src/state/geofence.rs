@@ -0,0 +1,26 @@
+use crate::constants::{MAX_GEOHASH_LEN, MAX_GEOHASH_PREFIXES};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct Geofence {
+    pub beacon: Pubkey,
+    #[max_len(MAX_GEOHASH_PREFIXES, MAX_GEOHASH_LEN)]
+    pub prefixes: Vec<String>,
+}
+
+impl Geofence {
+    pub fn is_valid(&self) -> bool {
+        !self.prefixes.is_empty()
+            && self.prefixes.len() <= MAX_GEOHASH_PREFIXES
+            && self
+                .prefixes
+                .iter()
+                .all(|prefix| !prefix.is_empty() && prefix.len() <= MAX_GEOHASH_LEN)
+    }
+
+    pub fn contains(&self, geohash: &str) -> bool {
+        self.prefixes
+            .iter()
+            .any(|prefix| geohash.starts_with(prefix.as_str()))
+    }
+}
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProducerLedger {
+    pub producer: Pubkey,
+    pub invoice_count: u64,
+    pub bump: u8,
+}
+
+impl ProducerLedger {
+    pub fn next_invoice_number(&self) -> u64 {
+        self.invoice_count + 1
+    }
+}
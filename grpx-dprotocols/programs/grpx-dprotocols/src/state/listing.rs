@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Listing {
+    pub token_mint_a: Pubkey,
+    pub producer: Pubkey,
+    pub offer: Pubkey,
+    pub bump: u8,
+}
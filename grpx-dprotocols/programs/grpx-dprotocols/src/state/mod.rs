@@ -1,3 +1,35 @@
+pub mod attributes;
+pub mod auction;
+pub mod barter;
+pub mod bid;
+pub mod cold_chain;
+pub mod collection_bid;
+pub mod counter;
+pub mod counter_offer;
+pub mod custody_chain;
+pub mod geofence;
+pub mod ledger;
+pub mod listing;
 pub mod offer;
+pub mod receipt;
+pub mod sealed_bid;
+pub mod shipping;
+pub mod tender;
 
+pub use attributes::*;
+pub use auction::*;
+pub use barter::*;
+pub use bid::*;
+pub use cold_chain::*;
+pub use collection_bid::*;
+pub use counter::*;
+pub use counter_offer::*;
+pub use custody_chain::*;
+pub use geofence::*;
+pub use ledger::*;
+pub use listing::*;
 pub use offer::*;
+pub use receipt::*;
+pub use sealed_bid::*;
+pub use shipping::*;
+pub use tender::*;
@@ -1,20 +1,60 @@
+use crate::constants::{
+    BREACH_REPORT_PREFIX, BUNDLE_ACCOUNTS_PER_MINT, DELIVERY_ATTESTATION_PREFIX,
+    DELIVERY_TIMEOUT_SECONDS, DISPUTE_WINDOW_SECONDS, LOCATION_ATTESTATION_PREFIX,
+    MAX_BARTER_MINTS, MAX_BUNDLE_MINTS,
+};
+use crate::error::GrpxProtocolError;
+use crate::instructions::is_native_mint;
+use crate::state::{BarterTerms, ColdChainTerms, Geofence, ShippingDetails};
 use anchor_lang::prelude::Space;
 use anchor_lang::prelude::*;
 
-#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+#[derive(Clone, Copy, Default, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
 pub enum OfferStatus {
+    #[default]
     Created,
     Accepted,
     Completed,
     Refunded,
+    Shipped,
+    InTransit,
+    Delivered,
+    Disputed,
 }
 
 impl Space for OfferStatus {
     const INIT_SPACE: usize = 1;
 }
 
+#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq, InitSpace)]
+pub enum OfferAllowlist {
+    Consumer(Pubkey),
+    /// Root of a Merkle tree whose leaves are `keccak(consumer)`, hashed in sorted pairs.
+    MerkleRoot([u8; 32]),
+}
+
+impl OfferAllowlist {
+    pub fn allows(&self, consumer: &Pubkey, proof: &[[u8; 32]]) -> bool {
+        match self {
+            OfferAllowlist::Consumer(allowed) => allowed == consumer,
+            OfferAllowlist::MerkleRoot(root) => {
+                let leaf = solana_keccak_hasher::hashv(&[consumer.as_ref()]).to_bytes();
+                let computed = proof.iter().fold(leaf, |node, sibling| {
+                    let (left, right) = if node <= *sibling {
+                        (node, *sibling)
+                    } else {
+                        (*sibling, node)
+                    };
+                    solana_keccak_hasher::hashv(&[&left, &right]).to_bytes()
+                });
+                computed == *root
+            }
+        }
+    }
+}
+
 #[account]
-#[derive(InitSpace)]
+#[derive(InitSpace, Default)]
 pub struct Offer {
     pub id: u64,
     pub producer: Pubkey,
@@ -22,7 +62,269 @@ pub struct Offer {
     pub token_mint_a: Pubkey,
     pub token_mint_b: Pubkey,
     pub token_a_offered_amount: u64,
+    pub token_a_remaining_amount: u64,
+    pub token_a_accepted_amount: u64,
+    /// Price in token B for each base unit of token A.
     pub token_b_desired_amount: u64,
+    #[max_len(MAX_BUNDLE_MINTS)]
+    pub bundle_mints: Vec<Pubkey>,
+    pub allowlist: Option<OfferAllowlist>,
+    pub expires_at: Option<i64>,
+    pub version: u32,
+    pub shipment_release_bps: u16,
+    pub carrier: Option<Pubkey>,
+    pub tracking_hash: Option<[u8; 32]>,
+    pub shipped_at: Option<i64>,
+    pub attestor: Option<Pubkey>,
+    pub delivered_at: Option<i64>,
+    pub delivery_attested: bool,
+    pub cold_chain: Option<ColdChainTerms>,
+    pub breach_penalty_bps: u16,
+    pub delivery_code_hash: Option<[u8; 32]>,
+    pub delivery_code_revealed: bool,
+    pub delivery_code_expires_at: Option<i64>,
+    pub requires_custody: bool,
+    pub geofence: Option<Geofence>,
+    pub location_verified: bool,
+    pub shipping_details: Option<ShippingDetails>,
+    pub listed: bool,
+    pub barter: Option<BarterTerms>,
+    #[max_len(MAX_BARTER_MINTS)]
+    pub barter_mints: Vec<Pubkey>,
+    /// Set on offers created by settling an auction or tender, so the winner cannot back out.
+    pub awarded: bool,
     pub status: OfferStatus,
     pub bump: u8,
 }
+
+impl Offer {
+    pub fn is_native(&self) -> bool {
+        is_native_mint(&self.token_mint_b)
+    }
+
+    pub fn price_for(&self, quantity: u64) -> Result<u64> {
+        quantity
+            .checked_mul(self.token_b_desired_amount)
+            .ok_or(GrpxProtocolError::ArithmeticOverflow.into())
+    }
+
+    pub fn allows(&self, consumer: &Pubkey, proof: &[[u8; 32]]) -> bool {
+        match &self.allowlist {
+            Some(allowlist) => allowlist.allows(consumer, proof),
+            None => true,
+        }
+    }
+
+    pub fn check_terms(&self, expected_version: u32) -> Result<()> {
+        require!(
+            self.version == expected_version,
+            GrpxProtocolError::OfferVersionMismatch
+        );
+
+        Ok(())
+    }
+
+    pub fn check_allowlist(&self, consumer: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+        require!(
+            self.allows(consumer, proof),
+            GrpxProtocolError::Unauthorized
+        );
+
+        Ok(())
+    }
+
+    pub fn check_quantity(&self, quantity: u64) -> Result<()> {
+        require!(
+            quantity > 0 && quantity <= self.token_a_remaining_amount,
+            GrpxProtocolError::InvalidQuantity
+        );
+        require!(
+            self.bundle_mints.is_empty() || quantity == self.token_a_remaining_amount,
+            GrpxProtocolError::InvalidQuantity
+        );
+
+        Ok(())
+    }
+
+    pub fn signer_seeds<'a>(&'a self, id: &'a [u8; 8]) -> [&'a [u8]; 4] {
+        [
+            b"offer",
+            self.producer.as_ref(),
+            id,
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now > expires_at)
+    }
+
+    pub fn is_awaiting_confirmation(&self) -> bool {
+        matches!(
+            self.status,
+            OfferStatus::Accepted
+                | OfferStatus::Shipped
+                | OfferStatus::InTransit
+                | OfferStatus::Delivered
+                | OfferStatus::Disputed
+        )
+    }
+
+    pub fn is_release_due(&self, now: i64) -> bool {
+        self.status == OfferStatus::Delivered
+            && (self.delivery_code_revealed
+                || (self.delivery_attested
+                    && self
+                        .delivered_at
+                        .is_some_and(|delivered_at| now >= delivered_at + DISPUTE_WINDOW_SECONDS)))
+    }
+
+    pub fn is_delivery_overdue(&self, now: i64) -> bool {
+        let deadline = match self.status {
+            OfferStatus::Shipped | OfferStatus::InTransit => self
+                .shipped_at
+                .map(|shipped_at| shipped_at + DELIVERY_TIMEOUT_SECONDS)
+                .into_iter()
+                .chain(self.delivery_code_expires_at)
+                .min(),
+            OfferStatus::Delivered
+                if self.attestor.is_some()
+                    && !self.delivery_attested
+                    && !self.delivery_code_revealed =>
+            {
+                self.delivered_at
+                    .map(|delivered_at| delivered_at + DELIVERY_TIMEOUT_SECONDS)
+            }
+            OfferStatus::Disputed => self
+                .delivered_at
+                .map(|delivered_at| delivered_at + DELIVERY_TIMEOUT_SECONDS),
+            _ => None,
+        };
+
+        deadline.is_some_and(|deadline| now >= deadline)
+    }
+
+    pub fn is_delivery_code_expired(&self, now: i64) -> bool {
+        self.delivery_code_expires_at
+            .is_some_and(|expires_at| now > expires_at)
+    }
+
+    pub fn is_disputable(&self, now: i64) -> bool {
+        self.status == OfferStatus::Delivered
+            && !self.delivery_code_revealed
+            && self
+                .delivered_at
+                .is_some_and(|delivered_at| now < delivered_at + DISPUTE_WINDOW_SECONDS)
+    }
+
+    pub fn delivery_message(&self, offer: &Pubkey) -> Vec<u8> {
+        let mut message = [DELIVERY_ATTESTATION_PREFIX, &self.fill_id(offer)].concat();
+        if let Some(shipping_details) = &self.shipping_details {
+            message.extend_from_slice(&shipping_details.hash());
+        }
+
+        message
+    }
+
+    pub fn breach_message(
+        &self,
+        offer: &Pubkey,
+        min_recorded: i16,
+        max_recorded: i16,
+        excursions: u16,
+    ) -> Vec<u8> {
+        [
+            BREACH_REPORT_PREFIX,
+            &self.fill_id(offer),
+            &min_recorded.to_le_bytes(),
+            &max_recorded.to_le_bytes(),
+            &excursions.to_le_bytes(),
+        ]
+        .concat()
+    }
+
+    pub fn location_message(&self, offer: &Pubkey, geohash: &str) -> Vec<u8> {
+        [
+            LOCATION_ATTESTATION_PREFIX,
+            &self.fill_id(offer),
+            geohash.as_bytes(),
+        ]
+        .concat()
+    }
+
+    pub fn has_delivery_terms(&self) -> bool {
+        self.shipment_release_bps > 0
+            || self.attestor.is_some()
+            || self.cold_chain.is_some()
+            || self.requires_custody
+            || self.geofence.is_some()
+    }
+
+    pub fn is_location_cleared(&self) -> bool {
+        self.geofence.is_none() || self.location_verified
+    }
+
+    /// Identifies the current fill in signed reports, so a report for an earlier fill of a
+    /// partially filled offer cannot be replayed.
+    pub fn fill_id(&self, offer: &Pubkey) -> Vec<u8> {
+        let consumer = self.consumer.unwrap_or_default();
+        [
+            offer.as_ref(),
+            consumer.as_ref(),
+            &self.token_a_remaining_amount.to_le_bytes(),
+        ]
+        .concat()
+    }
+
+    pub fn matches_delivery_code(&self, code: &[u8]) -> bool {
+        self.delivery_code_hash
+            .is_some_and(|hash| solana_keccak_hasher::hash(code).to_bytes() == hash)
+    }
+
+    pub fn delivery_authority(&self) -> Pubkey {
+        self.carrier.unwrap_or(self.producer)
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.token_a_remaining_amount == 0
+    }
+
+    pub fn split_bundle_accounts<'a, 'info>(
+        &self,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<(&'a [AccountInfo<'info>], &'a [AccountInfo<'info>])> {
+        let bundle_len = self.bundle_mints.len() * BUNDLE_ACCOUNTS_PER_MINT;
+        require!(
+            accounts.len() >= bundle_len,
+            GrpxProtocolError::InvalidBundleAccounts
+        );
+
+        Ok(accounts.split_at(bundle_len))
+    }
+
+    pub fn split_barter_deposit_accounts<'a, 'info>(
+        &self,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<(&'a [AccountInfo<'info>], &'a [AccountInfo<'info>])> {
+        let barter_len = self.barter.as_ref().map_or(0, BarterTerms::account_count);
+        require!(
+            accounts.len() >= barter_len,
+            GrpxProtocolError::InvalidBarterAccounts
+        );
+
+        Ok(accounts.split_at(barter_len))
+    }
+
+    pub fn split_barter_accounts<'a, 'info>(
+        &self,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<(&'a [AccountInfo<'info>], &'a [AccountInfo<'info>])> {
+        let barter_len = self.barter_mints.len() * BUNDLE_ACCOUNTS_PER_MINT;
+        require!(
+            accounts.len() >= barter_len,
+            GrpxProtocolError::InvalidBarterAccounts
+        );
+
+        Ok(accounts.split_at(barter_len))
+    }
+}
@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Receipt {
+    pub invoice_number: u64,
+    pub producer: Pubkey,
+    pub consumer: Pubkey,
+    pub offer: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub quantity: u64,
+    /// Price paid in token B for the whole quantity, before any breach refund.
+    pub token_b_amount: u64,
+    pub breach_penalty_bps: u16,
+    pub settled_at: i64,
+    pub bump: u8,
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct SealedBid {
+    pub tender: Pubkey,
+    pub bidder: Pubkey,
+    /// `keccak(bidder || amount_le || salt)`.
+    pub commitment: [u8; 32],
+    pub revealed_amount: Option<u64>,
+    pub bump: u8,
+}
+
+impl SealedBid {
+    pub fn matches(&self, amount: u64, salt: &[u8; 32]) -> bool {
+        let hash =
+            solana_keccak_hasher::hashv(&[self.bidder.as_ref(), &amount.to_le_bytes(), salt]);
+        hash.to_bytes() == self.commitment
+    }
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+/// Shipping address sealed by the consumer to the producer's X25519 key. The ciphertext is
+/// not counted in `Offer::INIT_SPACE`; the offer is reallocated to fit it on accept and
+/// shrunk back once the fill settles.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct ShippingDetails {
+    pub ephemeral_public_key: [u8; 32],
+    pub nonce: [u8; 24],
+    #[max_len(0)]
+    pub ciphertext: Vec<u8>,
+}
+
+impl ShippingDetails {
+    pub fn hash(&self) -> [u8; 32] {
+        solana_keccak_hasher::hashv(&[&self.ephemeral_public_key, &self.nonce, &self.ciphertext])
+            .to_bytes()
+    }
+}
@@ -0,0 +1,69 @@
+use crate::instructions::is_native_mint;
+use anchor_lang::prelude::Space;
+use anchor_lang::prelude::*;
+
+#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub enum TenderPricing {
+    FirstPrice,
+    SecondPrice,
+}
+
+impl Space for TenderPricing {
+    const INIT_SPACE: usize = 1;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Tender {
+    pub id: u64,
+    pub producer: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub reserve_price: u64,
+    pub deposit: u64,
+    pub pricing: TenderPricing,
+    pub commit_ends_at: i64,
+    pub reveal_ends_at: i64,
+    pub commitments: u32,
+    pub highest_bidder: Option<Pubkey>,
+    pub highest_bid: u64,
+    pub second_bid: u64,
+    pub settled: bool,
+    pub bump: u8,
+}
+
+impl Tender {
+    pub fn is_native(&self) -> bool {
+        is_native_mint(&self.token_mint_b)
+    }
+
+    pub fn is_committing(&self, now: i64) -> bool {
+        now < self.commit_ends_at
+    }
+
+    pub fn is_revealing(&self, now: i64) -> bool {
+        now >= self.commit_ends_at && now < self.reveal_ends_at
+    }
+
+    pub fn has_ended(&self, now: i64) -> bool {
+        now >= self.reveal_ends_at
+    }
+
+    /// Ranks a revealed bid. Ties go to the bid revealed first.
+    pub fn record_bid(&mut self, bidder: Pubkey, amount: u64) {
+        if self.highest_bidder.is_none() || amount > self.highest_bid {
+            self.second_bid = self.highest_bid;
+            self.highest_bid = amount;
+            self.highest_bidder = Some(bidder);
+        } else if amount > self.second_bid {
+            self.second_bid = amount;
+        }
+    }
+
+    pub fn clearing_price(&self) -> u64 {
+        match self.pricing {
+            TenderPricing::FirstPrice => self.highest_bid,
+            TenderPricing::SecondPrice => self.second_bid.max(self.reserve_price),
+        }
+    }
+}
@@ -0,0 +1,283 @@
+import * as anchor from '@coral-xyz/anchor'
+import { Program } from '@coral-xyz/anchor'
+
+import { GrpxDprotocols } from '../target/types/grpx_dprotocols'
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from '@solana/web3.js'
+import {
+  MINT_SIZE,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMint2Instruction,
+  createMintToInstruction,
+  getMinimumBalanceForRentExemptMint,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
+} from '@solana/spl-token'
+import { BN } from 'bn.js'
+import { randomBytes } from 'crypto'
+import { expect } from 'chai'
+
+const IDL = require('../target/idl/grpx_dprotocols.json')
+
+describe('grpx-dprotocols/auction', () => {
+  anchor.setProvider(anchor.AnchorProvider.env())
+  const provider = anchor.getProvider()
+  const connection = provider.connection
+  const program = new Program<GrpxDprotocols>(IDL, provider)
+  const tokenProgram = TOKEN_2022_PROGRAM_ID
+
+  const confirm = async (signature: string): Promise<string> => {
+    const block = await connection.getLatestBlockhash()
+
+    await connection.confirmTransaction({
+      signature,
+      ...block,
+    })
+
+    return signature
+  }
+
+  const log = async (signature: string): Promise<string> => {
+    console.log(
+      `Your transaction signature: https://explorer.solana.com/transaction/${signature}?cluster=${
+        connection.rpcEndpoint.split('.')[1]
+      }`,
+    )
+
+    return signature
+  }
+
+  const createSetup = () => {
+    const [producer, alice, bob, tokenMintA, tokenMintB] = Array.from({ length: 5 }, () => Keypair.generate())
+
+    const tokenAccount = (mint: Keypair, owner: PublicKey) =>
+      getAssociatedTokenAddressSync(mint.publicKey, owner, true, tokenProgram)
+
+    const id = new BN(randomBytes(8))
+    const auction = PublicKey.findProgramAddressSync(
+      [Buffer.from('auction'), producer.publicKey.toBuffer(), id.toArrayLike(Buffer, 'le', 8)],
+      program.programId,
+    )[0]
+
+    const accounts = {
+      producer: producer.publicKey,
+      tokenMintA: tokenMintA.publicKey,
+      tokenMintB: tokenMintB.publicKey,
+      producerTokenAccountA: tokenAccount(tokenMintA, producer.publicKey),
+      auction,
+      vaultTokenAccountA: tokenAccount(tokenMintA, auction),
+      vaultTokenAccountB: tokenAccount(tokenMintB, auction),
+      tokenProgram,
+    }
+
+    return { producer, alice, bob, tokenMintA, tokenMintB, id, auction, tokenAccount, accounts }
+  }
+
+  const initialize = async (setup: ReturnType<typeof createSetup>) => {
+    let lamports = await getMinimumBalanceForRentExemptMint(connection)
+    let tx = new Transaction()
+
+    tx.instructions = [
+      ...[setup.producer, setup.alice, setup.bob].map((a) =>
+        SystemProgram.transfer({
+          fromPubkey: provider.publicKey,
+          toPubkey: a.publicKey,
+          lamports: 0.1 * LAMPORTS_PER_SOL,
+        }),
+      ),
+      ...[setup.tokenMintA, setup.tokenMintB].map((m) =>
+        SystemProgram.createAccount({
+          fromPubkey: provider.publicKey,
+          newAccountPubkey: m.publicKey,
+          lamports,
+          space: MINT_SIZE,
+          programId: tokenProgram,
+        }),
+      ),
+      createInitializeMint2Instruction(setup.tokenMintA.publicKey, 0, setup.producer.publicKey, null, tokenProgram),
+      createAssociatedTokenAccountIdempotentInstruction(
+        provider.publicKey,
+        setup.accounts.producerTokenAccountA,
+        setup.producer.publicKey,
+        setup.tokenMintA.publicKey,
+        tokenProgram,
+      ),
+      createMintToInstruction(
+        setup.tokenMintA.publicKey,
+        setup.accounts.producerTokenAccountA,
+        setup.producer.publicKey,
+        1,
+        undefined,
+        tokenProgram,
+      ),
+      createInitializeMint2Instruction(setup.tokenMintB.publicKey, 0, provider.publicKey, null, tokenProgram),
+      ...[setup.alice, setup.bob].flatMap((a) => [
+        createAssociatedTokenAccountIdempotentInstruction(
+          provider.publicKey,
+          setup.tokenAccount(setup.tokenMintB, a.publicKey),
+          a.publicKey,
+          setup.tokenMintB.publicKey,
+          tokenProgram,
+        ),
+        createMintToInstruction(
+          setup.tokenMintB.publicKey,
+          setup.tokenAccount(setup.tokenMintB, a.publicKey),
+          provider.publicKey,
+          100,
+          undefined,
+          tokenProgram,
+        ),
+      ]),
+    ]
+
+    await provider.sendAndConfirm(tx, [setup.producer, setup.tokenMintA, setup.tokenMintB]).then(log)
+  }
+
+  const endsAt = () => new BN(Math.floor(Date.now() / 1000) + 3600)
+
+  describe('Bidding Flow', () => {
+    const setup = createSetup()
+
+    it('Initialization', async () => {
+      await initialize(setup)
+    })
+
+    it('StartAuction: locks the NFT', async () => {
+      await program.methods
+        .startAuction(setup.id, new BN(10), new BN(5), endsAt())
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const balance = await connection.getTokenAccountBalance(setup.accounts.vaultTokenAccountA)
+      expect(balance.value.amount).to.equal('1')
+    })
+
+    it('PlaceAuctionBid: rejects bids below the reserve', async () => {
+      try {
+        await program.methods
+          .placeAuctionBid(new BN(9))
+          .accounts({
+            ...setup.accounts,
+            bidder: setup.alice.publicKey,
+            previousBidder: null,
+            bidderTokenAccountB: setup.tokenAccount(setup.tokenMintB, setup.alice.publicKey),
+            previousBidderTokenAccountB: null,
+          })
+          .signers([setup.alice])
+          .rpc()
+        expect.fail('bid below the reserve was accepted')
+      } catch (err) {
+        expect(err.toString()).to.include('BidTooLow')
+      }
+    })
+
+    it('PlaceAuctionBid: opening bid', async () => {
+      await program.methods
+        .placeAuctionBid(new BN(10))
+        .accounts({
+          ...setup.accounts,
+          bidder: setup.alice.publicKey,
+          previousBidder: null,
+          bidderTokenAccountB: setup.tokenAccount(setup.tokenMintB, setup.alice.publicKey),
+          previousBidderTokenAccountB: null,
+        })
+        .signers([setup.alice])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('PlaceAuctionBid: outbid bidder is refunded', async () => {
+      await program.methods
+        .placeAuctionBid(new BN(15))
+        .accounts({
+          ...setup.accounts,
+          bidder: setup.bob.publicKey,
+          previousBidder: setup.alice.publicKey,
+          bidderTokenAccountB: setup.tokenAccount(setup.tokenMintB, setup.bob.publicKey),
+          previousBidderTokenAccountB: setup.tokenAccount(setup.tokenMintB, setup.alice.publicKey),
+        })
+        .signers([setup.bob])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const refund = await connection.getTokenAccountBalance(setup.tokenAccount(setup.tokenMintB, setup.alice.publicKey))
+      expect(refund.value.amount).to.equal('100')
+
+      const auction = await program.account.auction.fetch(setup.auction)
+      expect(auction.highestBidder.toBase58()).to.equal(setup.bob.publicKey.toBase58())
+      expect(auction.highestBid.toNumber()).to.equal(15)
+    })
+
+    it('CancelAuction: fails once bids exist', async () => {
+      try {
+        await program.methods.cancelAuction().accounts({ ...setup.accounts }).signers([setup.producer]).rpc()
+        expect.fail('auction with bids was cancelled')
+      } catch (err) {
+        expect(err.toString()).to.include('AuctionHasBids')
+      }
+    })
+
+    it('SettleAuction: fails before the end time', async () => {
+      const id = new BN(0)
+      const offer = PublicKey.findProgramAddressSync(
+        [Buffer.from('offer'), setup.producer.publicKey.toBuffer(), id.toArrayLike(Buffer, 'le', 8)],
+        program.programId,
+      )[0]
+
+      try {
+        await program.methods
+          .settleAuction()
+          .accounts({
+            ...setup.accounts,
+            initiator: setup.producer.publicKey,
+            auctionVaultTokenAccountA: setup.accounts.vaultTokenAccountA,
+            auctionVaultTokenAccountB: setup.accounts.vaultTokenAccountB,
+            offer,
+            vaultTokenAccountA: setup.tokenAccount(setup.tokenMintA, offer),
+            vaultTokenAccountB: setup.tokenAccount(setup.tokenMintB, offer),
+          })
+          .signers([setup.producer])
+          .rpc()
+        expect.fail('auction settled before its end time')
+      } catch (err) {
+        expect(err.toString()).to.include('AuctionNotEnded')
+      }
+    })
+  })
+
+  describe('Cancellation Flow', () => {
+    const setup = createSetup()
+
+    it('Initialization', async () => {
+      await initialize(setup)
+    })
+
+    it('StartAuction', async () => {
+      await program.methods
+        .startAuction(setup.id, new BN(10), new BN(5), endsAt())
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('CancelAuction: NFT returns to the producer', async () => {
+      await program.methods
+        .cancelAuction()
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const balance = await connection.getTokenAccountBalance(setup.accounts.producerTokenAccountA)
+      expect(balance.value.amount).to.equal('1')
+      expect(await connection.getAccountInfo(setup.auction)).to.be.null
+    })
+  })
+})
@@ -0,0 +1,355 @@
+import * as anchor from '@coral-xyz/anchor'
+import { Program } from '@coral-xyz/anchor'
+
+import { GrpxDprotocols } from '../target/types/grpx_dprotocols'
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from '@solana/web3.js'
+import {
+  MINT_SIZE,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMint2Instruction,
+  createMintToInstruction,
+  getMinimumBalanceForRentExemptMint,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token'
+import { BN } from 'bn.js'
+import { randomBytes } from 'crypto'
+import { expect } from 'chai'
+
+const IDL = require('../target/idl/grpx_dprotocols.json')
+
+describe('grpx-dprotocols/bidding', () => {
+  anchor.setProvider(anchor.AnchorProvider.env())
+  const provider = anchor.getProvider()
+  const connection = provider.connection
+  const program = new Program<GrpxDprotocols>(IDL, provider)
+  const tokenProgram = TOKEN_2022_PROGRAM_ID
+
+  const confirm = async (signature: string): Promise<string> => {
+    const block = await connection.getLatestBlockhash()
+
+    await connection.confirmTransaction({
+      signature,
+      ...block,
+    })
+
+    return signature
+  }
+
+  const log = async (signature: string): Promise<string> => {
+    console.log(
+      `Your transaction signature: https://explorer.solana.com/transaction/${signature}?cluster=${
+        connection.rpcEndpoint.split('.')[1]
+      }`,
+    )
+
+    return signature
+  }
+
+  const receiptFor = async (producer: PublicKey): Promise<PublicKey> => {
+    const ledger = PublicKey.findProgramAddressSync([Buffer.from('ledger'), producer.toBuffer()], program.programId)[0]
+    const invoiceCount = (await program.account.producerLedger.fetchNullable(ledger))?.invoiceCount ?? new BN(0)
+
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('receipt'), producer.toBuffer(), invoiceCount.addn(1).toArrayLike(Buffer, 'le', 8)],
+      program.programId,
+    )[0]
+  }
+
+  const createSetup = () => {
+    const [holder, bidder, tokenMintA, tokenMintB] = Array.from({ length: 4 }, () => Keypair.generate())
+
+    const [holderTokenAccountA, holderTokenAccountB, bidderTokenAccountA, bidderTokenAccountB] = [holder, bidder]
+      .map((a) =>
+        [tokenMintA, tokenMintB].map((m) =>
+          getAssociatedTokenAddressSync(m.publicKey, a.publicKey, false, tokenProgram),
+        ),
+      )
+      .flat()
+
+    const bid = PublicKey.findProgramAddressSync(
+      [Buffer.from('bid'), bidder.publicKey.toBuffer(), tokenMintA.publicKey.toBuffer()],
+      program.programId,
+    )[0]
+    const id = new BN(0)
+    const offer = PublicKey.findProgramAddressSync(
+      [Buffer.from('offer'), holder.publicKey.toBuffer(), id.toArrayLike(Buffer, 'le', 8)],
+      program.programId,
+    )[0]
+
+    const accounts = {
+      holder: holder.publicKey,
+      bidder: bidder.publicKey,
+      tokenMintA: tokenMintA.publicKey,
+      tokenMintB: tokenMintB.publicKey,
+      holderTokenAccountA,
+      holderTokenAccountB,
+      bidderTokenAccountA,
+      bidderTokenAccountB,
+      bid,
+      vaultTokenAccountB: getAssociatedTokenAddressSync(tokenMintB.publicKey, bid, true, tokenProgram),
+      tokenProgram,
+    }
+
+    const deliveryAccounts = {
+      ...accounts,
+      bidVaultTokenAccountB: accounts.vaultTokenAccountB,
+      offer,
+      vaultTokenAccountA: getAssociatedTokenAddressSync(tokenMintA.publicKey, offer, true, tokenProgram),
+      vaultTokenAccountB: getAssociatedTokenAddressSync(tokenMintB.publicKey, offer, true, tokenProgram),
+    }
+
+    return { holder, bidder, tokenMintA, tokenMintB, id, bid, offer, accounts, deliveryAccounts }
+  }
+
+  const initialize = async (setup: ReturnType<typeof createSetup>) => {
+    let lamports = await getMinimumBalanceForRentExemptMint(connection)
+    let tx = new Transaction()
+
+    tx.instructions = [
+      ...[setup.holder, setup.bidder].map((a) =>
+        SystemProgram.transfer({
+          fromPubkey: provider.publicKey,
+          toPubkey: a.publicKey,
+          lamports: 0.1 * LAMPORTS_PER_SOL,
+        }),
+      ),
+      ...[setup.tokenMintA, setup.tokenMintB].map((m) =>
+        SystemProgram.createAccount({
+          fromPubkey: provider.publicKey,
+          newAccountPubkey: m.publicKey,
+          lamports,
+          space: MINT_SIZE,
+          programId: tokenProgram,
+        }),
+      ),
+      ...[
+        {
+          mint: setup.tokenMintA.publicKey,
+          authority: setup.holder.publicKey,
+          ata: setup.accounts.holderTokenAccountA,
+          amount: 1,
+        },
+        {
+          mint: setup.tokenMintB.publicKey,
+          authority: setup.bidder.publicKey,
+          ata: setup.accounts.bidderTokenAccountB,
+          amount: 10,
+        },
+      ].flatMap((x) => [
+        createInitializeMint2Instruction(x.mint, 0, x.authority, null, tokenProgram),
+        createAssociatedTokenAccountIdempotentInstruction(provider.publicKey, x.ata, x.authority, x.mint, tokenProgram),
+        createMintToInstruction(x.mint, x.ata, x.authority, x.amount, undefined, tokenProgram),
+      ]),
+    ]
+
+    await provider.sendAndConfirm(tx, [setup.holder, setup.bidder, setup.tokenMintA, setup.tokenMintB]).then(log)
+  }
+
+  const expiresAt = () => new BN(Math.floor(Date.now() / 1000) + 3600)
+
+  describe('Swap Flow', () => {
+    const setup = createSetup()
+
+    it('Initialization', async () => {
+      await initialize(setup)
+    })
+
+    it('PlaceBid: rejects a mint that is not a single NFT', async () => {
+      try {
+        await program.methods
+          .placeBid(new BN(5), expiresAt())
+          .accounts({
+            ...setup.accounts,
+            tokenMintA: setup.tokenMintB.publicKey,
+            bid: PublicKey.findProgramAddressSync(
+              [Buffer.from('bid'), setup.bidder.publicKey.toBuffer(), setup.tokenMintB.publicKey.toBuffer()],
+              program.programId,
+            )[0],
+          })
+          .signers([setup.bidder])
+          .rpc()
+        expect.fail('bid placed on a fungible mint')
+      } catch (err) {
+        expect(err.toString()).to.include('InvalidNftMint')
+      }
+    })
+
+    it('PlaceBid', async () => {
+      await program.methods
+        .placeBid(new BN(5), expiresAt())
+        .accounts({ ...setup.accounts })
+        .signers([setup.bidder])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('AcceptBid: swaps atomically', async () => {
+      await program.methods
+        .acceptBid()
+        .accounts({ ...setup.accounts })
+        .signers([setup.holder])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const balance = await connection.getTokenAccountBalance(setup.accounts.holderTokenAccountB)
+      expect(balance.value.amount).to.equal('5')
+      expect(await connection.getAccountInfo(setup.bid)).to.be.null
+    })
+  })
+
+  describe('Delivery Flow', () => {
+    const setup = createSetup()
+
+    it('Initialization', async () => {
+      await initialize(setup)
+    })
+
+    it('PlaceBid', async () => {
+      await program.methods
+        .placeBid(new BN(5), expiresAt())
+        .accounts({ ...setup.accounts })
+        .signers([setup.bidder])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('AcceptBidForDelivery: opens an accepted offer', async () => {
+      await program.methods
+        .acceptBidForDelivery()
+        .accounts({ ...setup.deliveryAccounts })
+        .signers([setup.holder])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const offer = await program.account.offer.fetch(setup.offer)
+      expect(offer.consumer.toBase58()).to.equal(setup.bidder.publicKey.toBase58())
+      expect(offer.status).to.have.property('accepted')
+    })
+
+    it('ConfirmOffer: bidder confirms delivery', async () => {
+      await program.methods
+        .confirm()
+        .accounts({
+          producer: setup.holder.publicKey,
+          consumer: setup.bidder.publicKey,
+          initiator: setup.bidder.publicKey,
+          receipt: await receiptFor(setup.holder.publicKey),
+          tokenMintA: setup.tokenMintA.publicKey,
+          tokenMintB: setup.tokenMintB.publicKey,
+          producerTokenAccountB: setup.accounts.holderTokenAccountB,
+          consumerTokenAccountA: setup.accounts.bidderTokenAccountA,
+          offer: setup.offer,
+          vaultTokenAccountA: setup.deliveryAccounts.vaultTokenAccountA,
+          vaultTokenAccountB: setup.deliveryAccounts.vaultTokenAccountB,
+          tokenProgram,
+        })
+        .signers([setup.bidder])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+  })
+
+  describe('Cancellation Flow', () => {
+    const setup = createSetup()
+
+    it('Initialization', async () => {
+      await initialize(setup)
+    })
+
+    it('PlaceBid', async () => {
+      await program.methods
+        .placeBid(new BN(5), expiresAt())
+        .accounts({ ...setup.accounts })
+        .signers([setup.bidder])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('CancelBid: funds return to the bidder', async () => {
+      await program.methods
+        .cancelBid()
+        .accounts({ ...setup.accounts, initiator: setup.bidder.publicKey })
+        .signers([setup.bidder])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const balance = await connection.getTokenAccountBalance(setup.accounts.bidderTokenAccountB)
+      expect(balance.value.amount).to.equal('10')
+    })
+  })
+
+  describe('Collection Bid Flow', () => {
+    const setup = createSetup()
+    const collectionMint = Keypair.generate()
+    const id = new BN(randomBytes(8))
+    const collectionBid = PublicKey.findProgramAddressSync(
+      [Buffer.from('collection_bid'), setup.bidder.publicKey.toBuffer(), id.toArrayLike(Buffer, 'le', 8)],
+      program.programId,
+    )[0]
+    const accounts = {
+      bidder: setup.bidder.publicKey,
+      collectionMint: collectionMint.publicKey,
+      tokenMintB: setup.tokenMintB.publicKey,
+      bidderTokenAccountB: setup.accounts.bidderTokenAccountB,
+      collectionBid,
+      vaultTokenAccountB: getAssociatedTokenAddressSync(setup.tokenMintB.publicKey, collectionBid, true, tokenProgram),
+      tokenProgram,
+    }
+
+    it('Initialization', async () => {
+      await initialize(setup)
+
+      const tx = new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: provider.publicKey,
+          newAccountPubkey: collectionMint.publicKey,
+          lamports: await getMinimumBalanceForRentExemptMint(connection),
+          space: MINT_SIZE,
+          programId: TOKEN_PROGRAM_ID,
+        }),
+        createInitializeMint2Instruction(collectionMint.publicKey, 0, provider.publicKey, null, TOKEN_PROGRAM_ID),
+      )
+      await provider.sendAndConfirm(tx, [collectionMint]).then(log)
+    })
+
+    it('PlaceCollectionBid: escrows price times quantity', async () => {
+      await program.methods
+        .placeCollectionBid(id, new BN(2), new BN(3), { traitType: 'vintage', value: '2015' }, expiresAt())
+        .accounts({ ...accounts })
+        .signers([setup.bidder])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const bid = await program.account.collectionBid.fetch(collectionBid)
+      expect(bid.quantityRemaining.toNumber()).to.equal(3)
+      expect(bid.requiredAttribute.value).to.equal('2015')
+
+      const balance = await connection.getTokenAccountBalance(accounts.vaultTokenAccountB)
+      expect(balance.value.amount).to.equal('6')
+    })
+
+    it('CancelCollectionBid: funds return to the bidder', async () => {
+      await program.methods
+        .cancelCollectionBid()
+        .accounts({ ...accounts, initiator: setup.bidder.publicKey })
+        .signers([setup.bidder])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const balance = await connection.getTokenAccountBalance(accounts.bidderTokenAccountB)
+      expect(balance.value.amount).to.equal('10')
+      expect(await connection.getAccountInfo(collectionBid)).to.be.null
+    })
+  })
+})
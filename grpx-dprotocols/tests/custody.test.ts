@@ -0,0 +1,264 @@
+import * as anchor from '@coral-xyz/anchor'
+import { Program } from '@coral-xyz/anchor'
+
+import { GrpxDprotocols } from '../target/types/grpx_dprotocols'
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from '@solana/web3.js'
+import {
+  MINT_SIZE,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMint2Instruction,
+  createMintToInstruction,
+  getMinimumBalanceForRentExemptMint,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
+} from '@solana/spl-token'
+import { BN } from 'bn.js'
+import { randomBytes } from 'crypto'
+import { expect } from 'chai'
+
+const IDL = require('../target/idl/grpx_dprotocols.json')
+
+describe('grpx-dprotocols/custody', () => {
+  anchor.setProvider(anchor.AnchorProvider.env())
+  const provider = anchor.getProvider()
+  const connection = provider.connection
+  const program = new Program<GrpxDprotocols>(IDL, provider)
+  const tokenProgram = TOKEN_2022_PROGRAM_ID
+
+  const confirm = async (signature: string): Promise<string> => {
+    const block = await connection.getLatestBlockhash()
+
+    await connection.confirmTransaction({
+      signature,
+      ...block,
+    })
+
+    return signature
+  }
+
+  const log = async (signature: string): Promise<string> => {
+    console.log(
+      `Your transaction signature: https://explorer.solana.com/transaction/${signature}?cluster=${
+        connection.rpcEndpoint.split('.')[1]
+      }`,
+    )
+
+    return signature
+  }
+
+  const receiptFor = async (producer: PublicKey): Promise<PublicKey> => {
+    const ledger = PublicKey.findProgramAddressSync([Buffer.from('ledger'), producer.toBuffer()], program.programId)[0]
+    const invoiceCount = (await program.account.producerLedger.fetchNullable(ledger))?.invoiceCount ?? new BN(0)
+
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('receipt'), producer.toBuffer(), invoiceCount.addn(1).toArrayLike(Buffer, 'le', 8)],
+      program.programId,
+    )[0]
+  }
+
+  const [producer, exporter, consumer, tokenMintA, tokenMintB] = Array.from({ length: 5 }, () => Keypair.generate())
+
+  const [producerTokenAccountA, producerTokenAccountB, consumerTokenAccountA, consumerTokenAccountB] = [
+    producer,
+    consumer,
+  ]
+    .map((a) =>
+      [tokenMintA, tokenMintB].map((m) => getAssociatedTokenAddressSync(m.publicKey, a.publicKey, false, tokenProgram)),
+    )
+    .flat()
+
+  const id = new BN(0)
+  const offer = PublicKey.findProgramAddressSync(
+    [Buffer.from('offer'), producer.publicKey.toBuffer(), id.toArrayLike(Buffer, 'le', 8)],
+    program.programId,
+  )[0]
+  const custodyChain = PublicKey.findProgramAddressSync(
+    [Buffer.from('custody'), tokenMintA.publicKey.toBuffer()],
+    program.programId,
+  )[0]
+
+  const accounts = {
+    producer: producer.publicKey,
+    consumer: consumer.publicKey,
+    tokenMintA: tokenMintA.publicKey,
+    tokenMintB: tokenMintB.publicKey,
+    producerTokenAccountA,
+    producerTokenAccountB,
+    consumerTokenAccountA,
+    consumerTokenAccountB,
+    offer,
+    custodyChain,
+    vaultTokenAccountA: getAssociatedTokenAddressSync(tokenMintA.publicKey, offer, true, tokenProgram),
+    vaultTokenAccountB: getAssociatedTokenAddressSync(tokenMintB.publicKey, offer, true, tokenProgram),
+    listing: PublicKey.findProgramAddressSync(
+      [Buffer.from('listing'), tokenMintA.publicKey.toBuffer()],
+      program.programId,
+    )[0],
+    tokenProgram,
+  }
+
+  const handoff = (from: Keypair, to: Keypair, locationHash: number[] | null) =>
+    program.methods
+      .handoff(locationHash)
+      .accounts({ from: from.publicKey, to: to.publicKey, custodyChain })
+      .signers([from, to])
+
+  it('Initialization', async () => {
+    let lamports = await getMinimumBalanceForRentExemptMint(connection)
+    let tx = new Transaction()
+
+    tx.instructions = [
+      ...[producer, consumer].map((a) =>
+        SystemProgram.transfer({
+          fromPubkey: provider.publicKey,
+          toPubkey: a.publicKey,
+          lamports: 0.1 * LAMPORTS_PER_SOL,
+        }),
+      ),
+      ...[tokenMintA, tokenMintB].map((m) =>
+        SystemProgram.createAccount({
+          fromPubkey: provider.publicKey,
+          newAccountPubkey: m.publicKey,
+          lamports,
+          space: MINT_SIZE,
+          programId: tokenProgram,
+        }),
+      ),
+      ...[
+        { mint: tokenMintA.publicKey, authority: producer.publicKey, ata: producerTokenAccountA, amount: 1 },
+        { mint: tokenMintB.publicKey, authority: consumer.publicKey, ata: consumerTokenAccountB, amount: 100 },
+      ].flatMap((x) => [
+        createInitializeMint2Instruction(x.mint, 0, x.authority, null, tokenProgram),
+        createAssociatedTokenAccountIdempotentInstruction(provider.publicKey, x.ata, x.authority, x.mint, tokenProgram),
+        createMintToInstruction(x.mint, x.ata, x.authority, x.amount, undefined, tokenProgram),
+      ]),
+    ]
+
+    await provider.sendAndConfirm(tx, [producer, consumer, tokenMintA, tokenMintB]).then(log)
+  })
+
+  it('RegisterCustody: the holder becomes the first custodian', async () => {
+    await program.methods
+      .registerCustody()
+      .accounts({
+        custodian: producer.publicKey,
+        tokenMintA: tokenMintA.publicKey,
+        custodianTokenAccountA: producerTokenAccountA,
+        tokenProgram,
+      })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    const chain = await program.account.custodyChain.fetch(custodyChain)
+    expect(chain.custodian.toBase58()).to.equal(producer.publicKey.toBase58())
+  })
+
+  it('CreateOffer: confirmation requires custody to reach the consumer', async () => {
+    await program.methods
+      .open(new BN(1), new BN(100), null, null)
+      .accounts({ ...accounts })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    await program.methods
+      .amend({
+        tokenBDesiredAmount: new BN(100),
+        allowlist: null,
+        expiresAt: null,
+        shipmentReleaseBps: 0,
+        attestor: null,
+        coldChain: null,
+        requiresCustody: true,
+        geofence: null,
+        barter: null,
+      })
+      .accounts({ ...accounts })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+  })
+
+  it('AcceptOffer', async () => {
+    await program.methods
+      .accept(new BN(1), [], 1, null, null)
+      .accounts({ ...accounts })
+      .signers([consumer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+  })
+
+  it('Handoff: producer to exporter with a location hash', async () => {
+    await handoff(producer, exporter, Array.from(randomBytes(32))).rpc().then(confirm).then(log)
+  })
+
+  it('ConfirmOffer: rejected while the exporter holds the bottle', async () => {
+    try {
+      await program.methods
+        .confirm()
+        .accounts({ ...accounts, initiator: consumer.publicKey, receipt: await receiptFor(producer.publicKey) })
+        .signers([consumer])
+        .rpc()
+      expect.fail('offer confirmed before custody reached the consumer')
+    } catch (err) {
+      expect(err.toString()).to.include('CustodyNotTransferred')
+    }
+  })
+
+  it('Handoff: only the current custodian can hand off', async () => {
+    try {
+      await handoff(producer, consumer, null).rpc()
+      expect.fail('handoff by a former custodian')
+    } catch (err) {
+      expect(err.toString()).to.include('InvalidCustodian')
+    }
+  })
+
+  it('Handoff: exporter to consumer, history is recorded', async () => {
+    await handoff(exporter, consumer, null).rpc().then(confirm).then(log)
+
+    const chain = await program.account.custodyChain.fetch(custodyChain)
+    expect(chain.custodian.toBase58()).to.equal(consumer.publicKey.toBase58())
+    expect(chain.hops.map((hop) => hop.to.toBase58())).to.deep.equal([
+      exporter.publicKey.toBase58(),
+      consumer.publicKey.toBase58(),
+    ])
+    expect(chain.hops[0].locationHash).to.not.be.null
+  })
+
+  it('ConfirmOffer: settles once the consumer is the final custodian', async () => {
+    await program.methods
+      .confirm()
+      .accounts({ ...accounts, initiator: consumer.publicKey, receipt: await receiptFor(producer.publicKey) })
+      .signers([consumer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    const balance = await connection.getTokenAccountBalance(producerTokenAccountB)
+    expect(balance.value.amount).to.equal('100')
+  })
+
+  it('RegisterCustody: an existing chain cannot be restarted', async () => {
+    try {
+      await program.methods
+        .registerCustody()
+        .accounts({
+          custodian: consumer.publicKey,
+          tokenMintA: tokenMintA.publicKey,
+          custodianTokenAccountA: consumerTokenAccountA,
+          tokenProgram,
+        })
+        .signers([consumer])
+        .rpc()
+      expect.fail('custody chain restarted')
+    } catch (err) {
+      expect(err.toString()).to.include('already in use')
+    }
+  })
+})
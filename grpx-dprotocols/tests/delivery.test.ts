@@ -0,0 +1,499 @@
+import * as anchor from '@coral-xyz/anchor'
+import { Program } from '@coral-xyz/anchor'
+
+import { GrpxDprotocols } from '../target/types/grpx_dprotocols'
+import {
+  Ed25519Program,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  SystemProgram,
+  Transaction,
+} from '@solana/web3.js'
+import {
+  MINT_SIZE,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMint2Instruction,
+  createMintToInstruction,
+  getMinimumBalanceForRentExemptMint,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
+  unpackAccount,
+} from '@solana/spl-token'
+import { BankrunProvider, startAnchor } from 'anchor-bankrun'
+import { Clock } from 'solana-bankrun'
+import { BN } from 'bn.js'
+import { randomBytes } from 'crypto'
+import { expect } from 'chai'
+
+const IDL = require('../target/idl/grpx_dprotocols.json')
+const DELIVERY_TIMEOUT_SECONDS = 30 * 24 * 60 * 60
+
+describe('grpx-dprotocols/delivery', () => {
+  anchor.setProvider(anchor.AnchorProvider.env())
+  const provider = anchor.getProvider()
+  const connection = provider.connection
+  const program = new Program<GrpxDprotocols>(IDL, provider)
+  const tokenProgram = TOKEN_2022_PROGRAM_ID
+
+  const confirm = async (signature: string): Promise<string> => {
+    const block = await connection.getLatestBlockhash()
+
+    await connection.confirmTransaction({
+      signature,
+      ...block,
+    })
+
+    return signature
+  }
+
+  const log = async (signature: string): Promise<string> => {
+    console.log(
+      `Your transaction signature: https://explorer.solana.com/transaction/${signature}?cluster=${
+        connection.rpcEndpoint.split('.')[1]
+      }`,
+    )
+
+    return signature
+  }
+
+  const receiptFor = async (producer: PublicKey): Promise<PublicKey> => {
+    const ledger = PublicKey.findProgramAddressSync([Buffer.from('ledger'), producer.toBuffer()], program.programId)[0]
+    const invoiceCount = (await program.account.producerLedger.fetchNullable(ledger))?.invoiceCount ?? new BN(0)
+
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('receipt'), producer.toBuffer(), invoiceCount.addn(1).toArrayLike(Buffer, 'le', 8)],
+      program.programId,
+    )[0]
+  }
+
+  const [producer, consumer, carrier, sensor, beacon, tokenMintA, tokenMintB] = Array.from({ length: 7 }, () => Keypair.generate())
+
+  const [producerTokenAccountA, producerTokenAccountB, consumerTokenAccountA, consumerTokenAccountB] = [
+    producer,
+    consumer,
+  ]
+    .map((a) =>
+      [tokenMintA, tokenMintB].map((m) => getAssociatedTokenAddressSync(m.publicKey, a.publicKey, false, tokenProgram)),
+    )
+    .flat()
+
+  const id = new BN(0)
+  const offer = PublicKey.findProgramAddressSync(
+    [Buffer.from('offer'), producer.publicKey.toBuffer(), id.toArrayLike(Buffer, 'le', 8)],
+    program.programId,
+  )[0]
+
+  const accounts = {
+    producer: producer.publicKey,
+    consumer: consumer.publicKey,
+    tokenMintA: tokenMintA.publicKey,
+    tokenMintB: tokenMintB.publicKey,
+    producerTokenAccountA,
+    producerTokenAccountB,
+    consumerTokenAccountA,
+    consumerTokenAccountB,
+    offer,
+    vaultTokenAccountA: getAssociatedTokenAddressSync(tokenMintA.publicKey, offer, true, tokenProgram),
+    vaultTokenAccountB: getAssociatedTokenAddressSync(tokenMintB.publicKey, offer, true, tokenProgram),
+    listing: PublicKey.findProgramAddressSync(
+      [Buffer.from('listing'), tokenMintA.publicKey.toBuffer()],
+      program.programId,
+    )[0],
+    tokenProgram,
+  }
+
+  const trackingHash = Array.from(randomBytes(32))
+
+  it('Initialization', async () => {
+    let lamports = await getMinimumBalanceForRentExemptMint(connection)
+    let tx = new Transaction()
+
+    tx.instructions = [
+      ...[producer, consumer].map((a) =>
+        SystemProgram.transfer({
+          fromPubkey: provider.publicKey,
+          toPubkey: a.publicKey,
+          lamports: 0.1 * LAMPORTS_PER_SOL,
+        }),
+      ),
+      ...[tokenMintA, tokenMintB].map((m) =>
+        SystemProgram.createAccount({
+          fromPubkey: provider.publicKey,
+          newAccountPubkey: m.publicKey,
+          lamports,
+          space: MINT_SIZE,
+          programId: tokenProgram,
+        }),
+      ),
+      ...[
+        { mint: tokenMintA.publicKey, authority: producer.publicKey, ata: producerTokenAccountA, amount: 1 },
+        { mint: tokenMintB.publicKey, authority: consumer.publicKey, ata: consumerTokenAccountB, amount: 100 },
+      ].flatMap((x) => [
+        createInitializeMint2Instruction(x.mint, 0, x.authority, null, tokenProgram),
+        createAssociatedTokenAccountIdempotentInstruction(provider.publicKey, x.ata, x.authority, x.mint, tokenProgram),
+        createMintToInstruction(x.mint, x.ata, x.authority, x.amount, undefined, tokenProgram),
+      ]),
+    ]
+
+    await provider.sendAndConfirm(tx, [producer, consumer, tokenMintA, tokenMintB]).then(log)
+  })
+
+  it('CreateOffer', async () => {
+    await program.methods
+      .open(new BN(1), new BN(100), null, null)
+      .accounts({ ...accounts })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+  })
+
+  it('AmendOffer: releases 30% on shipment, names the carrier as attestor, adds cold-chain terms and a geofence', async () => {
+    await program.methods
+      .amend({
+        tokenBDesiredAmount: new BN(100),
+        allowlist: null,
+        expiresAt: null,
+        shipmentReleaseBps: 3000,
+        attestor: carrier.publicKey,
+        coldChain: {
+          sensor: sensor.publicKey,
+          minTemperature: 20,
+          maxTemperature: 80,
+          penaltyBpsPerExcursion: 500,
+          maxPenaltyBps: 2000,
+        },
+        requiresCustody: false,
+        geofence: { beacon: beacon.publicKey, prefixes: ['u09'] },
+        barter: null,
+      })
+      .accounts({ ...accounts })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+  })
+
+  it('AcceptOffer', async () => {
+    await program.methods
+      .accept(new BN(1), [], 1, null, null)
+      .accounts({ ...accounts })
+      .signers([consumer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+  })
+
+  it('UpdateDelivery: rejected before shipment', async () => {
+    try {
+      await program.methods
+        .updateDelivery({ inTransit: {} }, null)
+        .accounts({ authority: producer.publicKey, offer })
+        .signers([producer])
+        .rpc()
+      expect.fail('milestone reported before shipment')
+    } catch (err) {
+      expect(err.toString()).to.include('InvalidMilestone')
+    }
+  })
+
+  it('ShipOffer: producer ships while the shipment share stays in escrow', async () => {
+    await program.methods
+      .ship(trackingHash, carrier.publicKey)
+      .accounts({ ...accounts })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    const balance = await connection.getTokenAccountBalance(accounts.vaultTokenAccountB)
+    expect(balance.value.amount).to.equal('100')
+
+    const state = await program.account.offer.fetch(offer)
+    expect(state.status).to.have.property('shipped')
+    expect(state.trackingHash).to.deep.equal(trackingHash)
+  })
+
+  it('UpdateDelivery: only the carrier reports milestones', async () => {
+    try {
+      await program.methods
+        .updateDelivery({ inTransit: {} }, null)
+        .accounts({ authority: producer.publicKey, offer })
+        .signers([producer])
+        .rpc()
+      expect.fail('producer reported a carrier milestone')
+    } catch (err) {
+      expect(err.toString()).to.include('Unauthorized')
+    }
+
+    for (const milestone of [{ inTransit: {} }, { delivered: {} }]) {
+      await program.methods
+        .updateDelivery(milestone, null)
+        .accounts({ authority: carrier.publicKey, offer })
+        .signers([carrier])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    }
+
+    const state = await program.account.offer.fetch(offer)
+    expect(state.status).to.have.property('delivered')
+    expect(state.deliveredAt).to.not.be.null
+  })
+
+  it('RefundOffer: consumer cannot refund an unattested delivery before the timeout', async () => {
+    try {
+      await program.methods
+        .refund()
+        .accounts({ ...accounts, initiator: consumer.publicKey })
+        .signers([consumer])
+        .rpc()
+      expect.fail('delivery refunded before the timeout')
+    } catch (err) {
+      expect(err.toString()).to.include('InvalidOfferStatus')
+    }
+  })
+
+  const verifyLocation = (geohash: string) =>
+    program.methods
+      .verifyLocation(geohash)
+      .accounts({ submitter: provider.publicKey, offer, instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY })
+      .preInstructions([
+        Ed25519Program.createInstructionWithPrivateKey({
+          privateKey: beacon.secretKey,
+          message: Buffer.concat([
+            Buffer.from('grpx:location'),
+            offer.toBuffer(),
+            consumer.publicKey.toBuffer(),
+            new BN(1).toArrayLike(Buffer, 'le', 8),
+            Buffer.from(geohash),
+          ]),
+        }),
+      ])
+
+  it('VerifyLocation: rejects a reading outside the geofence', async () => {
+    try {
+      await verifyLocation('gcpvj0').rpc()
+      expect.fail('location outside the geofence accepted')
+    } catch (err) {
+      expect(err.toString()).to.include('OutsideGeofence')
+    }
+  })
+
+  it('VerifyLocation: beacon-signed reading inside the geofence', async () => {
+    await verifyLocation('u09tvw').rpc().then(confirm).then(log)
+
+    const state = await program.account.offer.fetch(offer)
+    expect(state.locationVerified).to.be.true
+  })
+
+  it('AttestDelivery: carrier-signed proof releases the shipment share and starts the dispute window', async () => {
+    const message = Buffer.concat([
+      Buffer.from('grpx:delivered'),
+      offer.toBuffer(),
+      consumer.publicKey.toBuffer(),
+      new BN(1).toArrayLike(Buffer, 'le', 8),
+    ])
+
+    await program.methods
+      .attestDelivery()
+      .accounts({ ...accounts, submitter: provider.publicKey, instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY })
+      .preInstructions([Ed25519Program.createInstructionWithPrivateKey({ privateKey: carrier.secretKey, message })])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    const balance = await connection.getTokenAccountBalance(producerTokenAccountB)
+    expect(balance.value.amount).to.equal('30')
+
+    const state = await program.account.offer.fetch(offer)
+    expect(state.deliveredAt).to.not.be.null
+  })
+
+  it('ReportBreach: sensor-signed readings out of range set a penalty', async () => {
+    const [minRecorded, maxRecorded, excursions] = [15, 90, 2]
+    const message = Buffer.concat([
+      Buffer.from('grpx:breach'),
+      offer.toBuffer(),
+      consumer.publicKey.toBuffer(),
+      new BN(1).toArrayLike(Buffer, 'le', 8),
+      new BN(minRecorded).toTwos(16).toArrayLike(Buffer, 'le', 2),
+      new BN(maxRecorded).toTwos(16).toArrayLike(Buffer, 'le', 2),
+      new BN(excursions).toArrayLike(Buffer, 'le', 2),
+    ])
+
+    await program.methods
+      .reportBreach({ minRecorded, maxRecorded, excursions })
+      .accounts({ submitter: provider.publicKey, offer, instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY })
+      .preInstructions([Ed25519Program.createInstructionWithPrivateKey({ privateKey: sensor.secretKey, message })])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    const state = await program.account.offer.fetch(offer)
+    expect(state.breachPenaltyBps).to.equal(1000)
+  })
+
+  it('ConfirmOffer: only the consumer can confirm inside the dispute window', async () => {
+    try {
+      await program.methods
+        .confirm()
+        .accounts({ ...accounts, initiator: carrier.publicKey, receipt: await receiptFor(producer.publicKey) })
+        .signers([carrier])
+        .rpc()
+      expect.fail('offer confirmed by a third party inside the dispute window')
+    } catch (err) {
+      expect(err.toString()).to.include('UnauthorizedConfirmation')
+    }
+  })
+
+  it('ConfirmOffer: refunds the breach penalty and releases the rest of the payment', async () => {
+    await program.methods
+      .confirm()
+      .accounts({ ...accounts, initiator: consumer.publicKey, receipt: await receiptFor(producer.publicKey) })
+      .signers([consumer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    const balance = await connection.getTokenAccountBalance(producerTokenAccountB)
+    expect(balance.value.amount).to.equal('93')
+
+    const refund = await connection.getTokenAccountBalance(consumerTokenAccountB)
+    expect(refund.value.amount).to.equal('7')
+  })
+})
+
+describe('grpx-dprotocols/delivery timeout', () => {
+  let context: Awaited<ReturnType<typeof startAnchor>>
+  let provider: BankrunProvider
+  let program: Program<GrpxDprotocols>
+  const tokenProgram = TOKEN_2022_PROGRAM_ID
+
+  const [producer, consumer, carrier, tokenMintA, tokenMintB] = Array.from({ length: 5 }, () => Keypair.generate())
+
+  const [producerTokenAccountA, producerTokenAccountB, consumerTokenAccountA, consumerTokenAccountB] = [
+    producer,
+    consumer,
+  ]
+    .map((a) =>
+      [tokenMintA, tokenMintB].map((m) => getAssociatedTokenAddressSync(m.publicKey, a.publicKey, false, tokenProgram)),
+    )
+    .flat()
+
+  const id = new BN(0)
+  let accounts: Record<string, PublicKey>
+
+  const balanceOf = async (address: PublicKey): Promise<string> => {
+    const info = await provider.connection.getAccountInfo(address)
+    return unpackAccount(address, info, tokenProgram).amount.toString()
+  }
+
+  before(async () => {
+    context = await startAnchor('', [], [])
+    provider = new BankrunProvider(context)
+    program = new Program<GrpxDprotocols>(IDL, provider)
+
+    const offer = PublicKey.findProgramAddressSync(
+      [Buffer.from('offer'), producer.publicKey.toBuffer(), id.toArrayLike(Buffer, 'le', 8)],
+      program.programId,
+    )[0]
+    accounts = {
+      producer: producer.publicKey,
+      consumer: consumer.publicKey,
+      tokenMintA: tokenMintA.publicKey,
+      tokenMintB: tokenMintB.publicKey,
+      producerTokenAccountA,
+      producerTokenAccountB,
+      consumerTokenAccountA,
+      consumerTokenAccountB,
+      offer,
+      vaultTokenAccountA: getAssociatedTokenAddressSync(tokenMintA.publicKey, offer, true, tokenProgram),
+      vaultTokenAccountB: getAssociatedTokenAddressSync(tokenMintB.publicKey, offer, true, tokenProgram),
+      listing: PublicKey.findProgramAddressSync(
+        [Buffer.from('listing'), tokenMintA.publicKey.toBuffer()],
+        program.programId,
+      )[0],
+      tokenProgram,
+    }
+
+    const lamports = Number((await context.banksClient.getRent()).minimumBalance(BigInt(MINT_SIZE)))
+    const tx = new Transaction()
+
+    tx.instructions = [
+      ...[producer, consumer].map((a) =>
+        SystemProgram.transfer({
+          fromPubkey: provider.publicKey,
+          toPubkey: a.publicKey,
+          lamports: 0.1 * LAMPORTS_PER_SOL,
+        }),
+      ),
+      ...[tokenMintA, tokenMintB].map((m) =>
+        SystemProgram.createAccount({
+          fromPubkey: provider.publicKey,
+          newAccountPubkey: m.publicKey,
+          lamports,
+          space: MINT_SIZE,
+          programId: tokenProgram,
+        }),
+      ),
+      ...[
+        { mint: tokenMintA.publicKey, authority: producer.publicKey, ata: producerTokenAccountA, amount: 1 },
+        { mint: tokenMintB.publicKey, authority: consumer.publicKey, ata: consumerTokenAccountB, amount: 100 },
+      ].flatMap((x) => [
+        createInitializeMint2Instruction(x.mint, 0, x.authority, null, tokenProgram),
+        createAssociatedTokenAccountIdempotentInstruction(provider.publicKey, x.ata, x.authority, x.mint, tokenProgram),
+        createMintToInstruction(x.mint, x.ata, x.authority, x.amount, undefined, tokenProgram),
+      ]),
+    ]
+
+    await provider.sendAndConfirm(tx, [producer, consumer, tokenMintA, tokenMintB])
+  })
+
+  it('RefundOffer: an overdue shipment refunds the consumer in full', async () => {
+    await program.methods.open(new BN(1), new BN(100), null, null).accounts(accounts).signers([producer]).rpc()
+    await program.methods
+      .amend({
+        tokenBDesiredAmount: new BN(100),
+        allowlist: null,
+        expiresAt: null,
+        shipmentReleaseBps: 3000,
+        attestor: carrier.publicKey,
+        coldChain: null,
+        requiresCustody: false,
+        geofence: null,
+        barter: null,
+      })
+      .accounts(accounts)
+      .signers([producer])
+      .rpc()
+    await program.methods.accept(new BN(1), [], 1, null, null).accounts(accounts).signers([consumer]).rpc()
+    await program.methods
+      .ship(Array.from(randomBytes(32)), carrier.publicKey)
+      .accounts(accounts)
+      .signers([producer])
+      .rpc()
+
+    const clock = await context.banksClient.getClock()
+    context.setClock(
+      new Clock(
+        clock.slot,
+        clock.epochStartTimestamp,
+        clock.epoch,
+        clock.leaderScheduleEpoch,
+        clock.unixTimestamp + BigInt(DELIVERY_TIMEOUT_SECONDS + 1),
+      ),
+    )
+
+    await program.methods
+      .refund()
+      .accounts({ ...accounts, initiator: consumer.publicKey })
+      .signers([consumer])
+      .rpc()
+
+    expect(await balanceOf(consumerTokenAccountB)).to.equal('100')
+    expect(await balanceOf(producerTokenAccountA)).to.equal('1')
+  })
+})
@@ -10,12 +10,18 @@ import {
   createMintToInstruction,
   getMinimumBalanceForRentExemptMint,
   getAssociatedTokenAddressSync,
+  NATIVE_MINT,
+  NATIVE_MINT_2022,
   TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
 } from '@solana/spl-token'
+import { keccak_256 } from '@noble/hashes/sha3'
 import { BN } from 'bn.js'
 import { randomBytes } from 'crypto'
+import { expect } from 'chai'
 
 const IDL = require('../target/idl/grpx_dprotocols.json')
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s')
 
 describe('grpx-dprotocols/escrow', () => {
   anchor.setProvider(anchor.AnchorProvider.env())
@@ -45,6 +51,30 @@ describe('grpx-dprotocols/escrow', () => {
     return signature
   }
 
+  // Events emitted with `emit_cpi!` land in self-invocations tagged with Anchor's event discriminator.
+  const EVENT_IX_TAG = Buffer.from('e445a52e51cb9a1d', 'hex')
+
+  const eventsOf = async (signature: string) => {
+    const tx = await connection.getTransaction(signature, { commitment: 'confirmed', maxSupportedTransactionVersion: 0 })
+
+    return (tx?.meta?.innerInstructions ?? [])
+      .flatMap((inner) => inner.instructions)
+      .map((ix) => Buffer.from(anchor.utils.bytes.bs58.decode(ix.data)))
+      .filter((data) => data.subarray(0, 8).equals(EVENT_IX_TAG))
+      .map((data) => program.coder.events.decode(anchor.utils.bytes.base64.encode(data.subarray(8))))
+      .filter((event) => event !== null)
+  }
+
+  const receiptFor = async (producer: PublicKey): Promise<PublicKey> => {
+    const ledger = PublicKey.findProgramAddressSync([Buffer.from('ledger'), producer.toBuffer()], program.programId)[0]
+    const invoiceCount = (await program.account.producerLedger.fetchNullable(ledger))?.invoiceCount ?? new BN(0)
+
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('receipt'), producer.toBuffer(), invoiceCount.addn(1).toArrayLike(Buffer, 'le', 8)],
+      program.programId,
+    )[0]
+  }
+
   const createSetup = () => {
     const [producer, consumer, tokenMintA, tokenMintB] = Array.from({ length: 4 }, () => Keypair.generate())
 
@@ -59,7 +89,8 @@ describe('grpx-dprotocols/escrow', () => {
       )
       .flat()
 
-    const id = new BN(randomBytes(8))
+    // Offer ids come from the producer's counter, so a fresh producer's first offer is 0.
+    const id = new BN(0)
     const offer = PublicKey.findProgramAddressSync(
       [Buffer.from('offer'), producer.publicKey.toBuffer(), id.toArrayLike(Buffer, 'le', 8)],
       program.programId,
@@ -67,6 +98,10 @@ describe('grpx-dprotocols/escrow', () => {
 
     const vaultTokenAccountA = getAssociatedTokenAddressSync(tokenMintA.publicKey, offer, true, tokenProgram)
     const vaultTokenAccountB = getAssociatedTokenAddressSync(tokenMintB.publicKey, offer, true, tokenProgram)
+    const listing = PublicKey.findProgramAddressSync(
+      [Buffer.from('listing'), tokenMintA.publicKey.toBuffer()],
+      program.programId,
+    )[0]
 
     const accounts = {
       producer: producer.publicKey,
@@ -80,6 +115,7 @@ describe('grpx-dprotocols/escrow', () => {
       offer,
       vaultTokenAccountA,
       vaultTokenAccountB,
+      listing,
       tokenProgram,
     }
 
@@ -94,6 +130,7 @@ describe('grpx-dprotocols/escrow', () => {
       consumerTokenAccountB,
       id,
       offer,
+      listing,
       vaultTokenAccountA,
       vaultTokenAccountB,
       accounts,
@@ -143,19 +180,31 @@ describe('grpx-dprotocols/escrow', () => {
       await provider.sendAndConfirm(tx, [setup.producer, setup.consumer, setup.tokenMintA, setup.tokenMintB]).then(log)
     })
 
-    it('CreateOffer', async () => {
-      await program.methods
-        .open(setup.id, new BN(1), new BN(1))
+    it('CreateOffer: takes the next id from the producer counter', async () => {
+      const signature = await program.methods
+        .open(new BN(1), new BN(1), null, null)
         .accounts({ ...setup.accounts })
         .signers([setup.producer])
         .rpc()
         .then(confirm)
         .then(log)
+
+      const [opened] = await eventsOf(signature)
+      expect(opened.name).to.equal('offerOpened')
+      expect(opened.data.offer.toBase58()).to.equal(setup.offer.toBase58())
+      expect(opened.data.status).to.have.property('created')
+
+      const counter = PublicKey.findProgramAddressSync(
+        [Buffer.from('producer_counter'), setup.producer.publicKey.toBuffer()],
+        program.programId,
+      )[0]
+      expect((await program.account.producerCounter.fetch(counter)).offerCount.toNumber()).to.equal(1)
+      expect((await program.account.offer.fetch(setup.offer)).id.toNumber()).to.equal(0)
     })
 
     it('AcceptOffer', async () => {
       await program.methods
-        .accept()
+        .accept(new BN(1), [], 0, null, null)
         .accounts({ ...setup.accounts })
         .signers([setup.consumer])
         .rpc()
@@ -163,14 +212,28 @@ describe('grpx-dprotocols/escrow', () => {
         .then(log)
     })
 
-    it('ConfirmOffer', async () => {
-      await program.methods
+    it('ConfirmOffer: files a receipt that outlives the offer', async () => {
+      const receipt = await receiptFor(setup.producer.publicKey)
+
+      const signature = await program.methods
         .confirm()
-        .accounts({ ...setup.accounts })
+        .accounts({ ...setup.accounts, initiator: setup.consumer.publicKey, receipt })
         .signers([setup.consumer])
         .rpc()
         .then(confirm)
         .then(log)
+
+      expect(await connection.getAccountInfo(setup.offer)).to.be.null
+
+      const [confirmed] = await eventsOf(signature)
+      expect(confirmed.name).to.equal('offerConfirmed')
+      expect(confirmed.data.invoiceNumber.toNumber()).to.equal(1)
+      expect(confirmed.data.status).to.have.property('completed')
+
+      const state = await program.account.receipt.fetch(receipt)
+      expect(state.invoiceNumber.toNumber()).to.equal(1)
+      expect(state.consumer.toBase58()).to.equal(setup.consumer.publicKey.toBase58())
+      expect(state.tokenBAmount.toNumber()).to.equal(1)
     })
   })
 
@@ -219,7 +282,7 @@ describe('grpx-dprotocols/escrow', () => {
 
     it('CreateOffer', async () => {
       await program.methods
-        .open(setup.id, new BN(1), new BN(1))
+        .open(new BN(1), new BN(1), null, null)
         .accounts({ ...setup.accounts })
         .signers([setup.producer])
         .rpc()
@@ -233,7 +296,18 @@ describe('grpx-dprotocols/escrow', () => {
         initiator: setup.producer.publicKey,
       }
       refundAccounts.vaultTokenAccountB = null
-      await program.methods.refund().accounts(refundAccounts).signers([setup.producer]).rpc().then(confirm).then(log)
+      const signature = await program.methods
+        .refund()
+        .accounts(refundAccounts)
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const [refunded] = await eventsOf(signature)
+      expect(refunded.name).to.equal('offerRefunded')
+      expect(refunded.data.tokenAAmount.toNumber()).to.equal(1)
+      expect(refunded.data.status).to.have.property('refunded')
     })
   })
 
@@ -282,7 +356,7 @@ describe('grpx-dprotocols/escrow', () => {
 
     it('CreateOffer', async () => {
       await program.methods
-        .open(setup.id, new BN(1), new BN(1))
+        .open(new BN(1), new BN(1), null, null)
         .accounts({ ...setup.accounts })
         .signers([setup.producer])
         .rpc()
@@ -292,7 +366,7 @@ describe('grpx-dprotocols/escrow', () => {
 
     it('AcceptOffer', async () => {
       await program.methods
-        .accept()
+        .accept(new BN(1), [], 0, null, null)
         .accounts({ ...setup.accounts })
         .signers([setup.consumer])
         .rpc()
@@ -354,7 +428,7 @@ describe('grpx-dprotocols/escrow', () => {
 
     it('CreateOffer', async () => {
       await program.methods
-        .open(setup.id, new BN(1), new BN(1))
+        .open(new BN(1), new BN(1), null, null)
         .accounts({ ...setup.accounts })
         .signers([setup.producer])
         .rpc()
@@ -364,7 +438,7 @@ describe('grpx-dprotocols/escrow', () => {
 
     it('AcceptOffer', async () => {
       await program.methods
-        .accept()
+        .accept(new BN(1), [], 0, null, null)
         .accounts({ ...setup.accounts })
         .signers([setup.consumer])
         .rpc()
@@ -380,4 +454,1451 @@ describe('grpx-dprotocols/escrow', () => {
       await program.methods.refund().accounts(refundAccounts).signers([setup.consumer]).rpc().then(confirm).then(log)
     })
   })
+
+  describe('Native Payment Flow', () => {
+    const setup = createSetup()
+    const accounts = {
+      ...setup.accounts,
+      tokenMintB: NATIVE_MINT_2022,
+      producerTokenAccountB: null,
+      consumerTokenAccountB: null,
+      vaultTokenAccountB: null,
+    }
+
+    it('Initialization', async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection)
+      let tx = new Transaction()
+
+      tx.instructions = [
+        ...[setup.producer, setup.consumer].map((a) =>
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: a.publicKey,
+            lamports: 0.1 * LAMPORTS_PER_SOL,
+          }),
+        ),
+        SystemProgram.createAccount({
+          fromPubkey: provider.publicKey,
+          newAccountPubkey: setup.tokenMintA.publicKey,
+          lamports,
+          space: MINT_SIZE,
+          programId: tokenProgram,
+        }),
+        createInitializeMint2Instruction(setup.tokenMintA.publicKey, 0, setup.producer.publicKey, null, tokenProgram),
+        createAssociatedTokenAccountIdempotentInstruction(
+          provider.publicKey,
+          setup.producerTokenAccountA,
+          setup.producer.publicKey,
+          setup.tokenMintA.publicKey,
+          tokenProgram,
+        ),
+        createMintToInstruction(
+          setup.tokenMintA.publicKey,
+          setup.producerTokenAccountA,
+          setup.producer.publicKey,
+          1,
+          undefined,
+          tokenProgram,
+        ),
+      ]
+
+      await provider.sendAndConfirm(tx, [setup.producer, setup.tokenMintA]).then(log)
+    })
+
+    it('CreateOffer: priced in lamports', async () => {
+      await program.methods
+        .open(new BN(1), new BN(0.05 * LAMPORTS_PER_SOL), null, null)
+        .accounts({ ...accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('AcceptOffer: lamports held by the offer', async () => {
+      await program.methods
+        .accept(new BN(1), [], 0, null, null)
+        .accounts({ ...accounts })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('ConfirmOffer: lamports released to the producer', async () => {
+      const [balance, listingRent] = await Promise.all(
+        [setup.producer.publicKey, setup.listing].map((account) => connection.getBalance(account)),
+      )
+
+      await program.methods
+        .confirm()
+        .accounts({
+          ...accounts,
+          initiator: setup.consumer.publicKey,
+          receipt: await receiptFor(setup.producer.publicKey),
+        })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const settled = await connection.getBalance(setup.producer.publicKey)
+      expect(settled - balance - listingRent).to.equal(0.05 * LAMPORTS_PER_SOL)
+    })
+  })
+
+  describe('Royalty Flow', () => {
+    const setup = createSetup()
+    const mintAuthority = PublicKey.findProgramAddressSync([Buffer.from('authority')], program.programId)[0]
+    const [metadataA, masterEditionA] = [[], [Buffer.from('edition')]].map(
+      (suffix) =>
+        PublicKey.findProgramAddressSync(
+          [
+            Buffer.from('metadata'),
+            TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+            setup.tokenMintA.publicKey.toBuffer(),
+            ...suffix,
+          ],
+          TOKEN_METADATA_PROGRAM_ID,
+        )[0],
+    )
+    // Forged NFTs live in the legacy token program, so the offer settles there too.
+    const accounts = {
+      ...setup.accounts,
+      tokenMintB: NATIVE_MINT,
+      producerTokenAccountA: getAssociatedTokenAddressSync(setup.tokenMintA.publicKey, setup.producer.publicKey),
+      consumerTokenAccountA: getAssociatedTokenAddressSync(setup.tokenMintA.publicKey, setup.consumer.publicKey),
+      vaultTokenAccountA: getAssociatedTokenAddressSync(setup.tokenMintA.publicKey, setup.offer, true),
+      producerTokenAccountB: null,
+      consumerTokenAccountB: null,
+      vaultTokenAccountB: null,
+      metadataA,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    }
+
+    it('Initialization', async () => {
+      const tx = new Transaction().add(
+        ...[setup.producer, setup.consumer].map((a) =>
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: a.publicKey,
+            lamports: 0.1 * LAMPORTS_PER_SOL,
+          }),
+        ),
+      )
+      await provider.sendAndConfirm(tx).then(log)
+    })
+
+    it('ForgeCollection: 5% royalty to the verified mint authority', async () => {
+      await program.methods
+        .create({ name: 'Royal', symbol: 'RYL', description: '', uri: '', sellerFeeBasisPoints: 500, attributes: null })
+        .accountsPartial({
+          owner: setup.producer.publicKey,
+          mint: setup.tokenMintA.publicKey,
+          mintAuthority,
+          metadata: metadataA,
+          masterEdition: masterEditionA,
+          destination: accounts.producerTokenAccountA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .signers([setup.producer, setup.tokenMintA])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('CreateOffer and AcceptOffer', async () => {
+      await program.methods
+        .open(new BN(1), new BN(0.05 * LAMPORTS_PER_SOL), null, null)
+        .accounts({ ...accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      await program.methods
+        .accept(new BN(1), [], 0, null, null)
+        .accounts({ ...accounts })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('ConfirmOffer: pays the creator its share before the producer', async () => {
+      // Closing the listing refunds its rent to the producer alongside the sale proceeds.
+      const [creatorBalance, producerBalance, listingRent] = await Promise.all(
+        [mintAuthority, setup.producer.publicKey, setup.listing].map((account) => connection.getBalance(account)),
+      )
+
+      await program.methods
+        .confirm()
+        .accounts({
+          ...accounts,
+          initiator: setup.consumer.publicKey,
+          receipt: await receiptFor(setup.producer.publicKey),
+        })
+        .remainingAccounts([{ pubkey: mintAuthority, isSigner: false, isWritable: true }])
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const [creatorSettled, producerSettled] = await Promise.all(
+        [mintAuthority, setup.producer.publicKey].map((account) => connection.getBalance(account)),
+      )
+      expect(creatorSettled - creatorBalance).to.equal(0.0025 * LAMPORTS_PER_SOL)
+      expect(producerSettled - producerBalance - listingRent).to.equal(0.0475 * LAMPORTS_PER_SOL)
+    })
+  })
+
+  describe('Bundle Flow', () => {
+    const setup = createSetup()
+    const bundleMints = Array.from({ length: 2 }, () => Keypair.generate())
+    const bundleAccounts = (owner: PublicKey) =>
+      bundleMints.flatMap((m) => [
+        { pubkey: m.publicKey, isSigner: false, isWritable: false },
+        {
+          pubkey: getAssociatedTokenAddressSync(m.publicKey, setup.offer, true, tokenProgram),
+          isSigner: false,
+          isWritable: true,
+        },
+        {
+          pubkey: getAssociatedTokenAddressSync(m.publicKey, owner, false, tokenProgram),
+          isSigner: false,
+          isWritable: true,
+        },
+      ])
+
+    it('Initialization', async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection)
+      let tx = new Transaction()
+
+      tx.instructions = [
+        ...[setup.producer, setup.consumer].map((a) =>
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: a.publicKey,
+            lamports: 0.1 * LAMPORTS_PER_SOL,
+          }),
+        ),
+        ...[setup.tokenMintA, setup.tokenMintB, ...bundleMints].map((m) =>
+          SystemProgram.createAccount({
+            fromPubkey: provider.publicKey,
+            newAccountPubkey: m.publicKey,
+            lamports,
+            space: MINT_SIZE,
+            programId: tokenProgram,
+          }),
+        ),
+        ...[
+          { mint: setup.tokenMintA.publicKey, authority: setup.producer.publicKey, decimals: 0, amount: 1 },
+          { mint: setup.tokenMintB.publicKey, authority: setup.consumer.publicKey, decimals: 6, amount: 2 },
+          ...bundleMints.map((m) => ({ mint: m.publicKey, authority: setup.producer.publicKey, decimals: 0, amount: 1 })),
+        ].flatMap((x) => {
+          const ata = getAssociatedTokenAddressSync(x.mint, x.authority, false, tokenProgram)
+          return [
+            createInitializeMint2Instruction(x.mint, x.decimals, x.authority, null, tokenProgram),
+            createAssociatedTokenAccountIdempotentInstruction(provider.publicKey, ata, x.authority, x.mint, tokenProgram),
+            createMintToInstruction(x.mint, ata, x.authority, x.amount, undefined, tokenProgram),
+          ]
+        }),
+      ]
+
+      await provider
+        .sendAndConfirm(tx, [setup.producer, setup.consumer, setup.tokenMintA, setup.tokenMintB, ...bundleMints])
+        .then(log)
+    })
+
+    it('CreateOffer: bundle of three', async () => {
+      await program.methods
+        .open(new BN(1), new BN(2), null, null)
+        .accounts({ ...setup.accounts })
+        .remainingAccounts(bundleAccounts(setup.producer.publicKey))
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('AcceptOffer', async () => {
+      await program.methods
+        .accept(new BN(1), [], 0, null, null)
+        .accounts({ ...setup.accounts })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('ConfirmOffer: settles every bundled NFT', async () => {
+      await program.methods
+        .confirm()
+        .accounts({
+          ...setup.accounts,
+          initiator: setup.consumer.publicKey,
+          receipt: await receiptFor(setup.producer.publicKey),
+        })
+        .remainingAccounts(bundleAccounts(setup.consumer.publicKey))
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      for (const m of bundleMints) {
+        const ata = getAssociatedTokenAddressSync(m.publicKey, setup.consumer.publicKey, false, tokenProgram)
+        const balance = await connection.getTokenAccountBalance(ata)
+        expect(balance.value.amount).to.equal('1')
+      }
+    })
+  })
+
+  describe('Partial Fill Flow', () => {
+    const setup = createSetup()
+    const secondConsumer = Keypair.generate()
+    const secondConsumerAccounts = {
+      ...setup.accounts,
+      consumer: secondConsumer.publicKey,
+      consumerTokenAccountA: getAssociatedTokenAddressSync(
+        setup.tokenMintA.publicKey,
+        secondConsumer.publicKey,
+        false,
+        tokenProgram,
+      ),
+      consumerTokenAccountB: getAssociatedTokenAddressSync(
+        setup.tokenMintB.publicKey,
+        secondConsumer.publicKey,
+        false,
+        tokenProgram,
+      ),
+    }
+
+    it('Initialization', async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection)
+      let tx = new Transaction()
+
+      tx.instructions = [
+        ...[setup.producer, setup.consumer, secondConsumer].map((a) =>
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: a.publicKey,
+            lamports: 0.1 * LAMPORTS_PER_SOL,
+          }),
+        ),
+        ...[setup.tokenMintA, setup.tokenMintB].map((m) =>
+          SystemProgram.createAccount({
+            fromPubkey: provider.publicKey,
+            newAccountPubkey: m.publicKey,
+            lamports,
+            space: MINT_SIZE,
+            programId: tokenProgram,
+          }),
+        ),
+        createInitializeMint2Instruction(setup.tokenMintA.publicKey, 0, setup.producer.publicKey, null, tokenProgram),
+        createInitializeMint2Instruction(setup.tokenMintB.publicKey, 6, provider.publicKey, null, tokenProgram),
+        ...[
+          { mint: setup.tokenMintA.publicKey, authority: setup.producer.publicKey, ata: setup.producerTokenAccountA },
+          { mint: setup.tokenMintB.publicKey, authority: setup.consumer.publicKey, ata: setup.consumerTokenAccountB },
+          {
+            mint: setup.tokenMintB.publicKey,
+            authority: secondConsumer.publicKey,
+            ata: secondConsumerAccounts.consumerTokenAccountB,
+          },
+        ].flatMap((x) => [
+          createAssociatedTokenAccountIdempotentInstruction(
+            provider.publicKey,
+            x.ata,
+            x.authority,
+            x.mint,
+            tokenProgram,
+          ),
+        ]),
+        createMintToInstruction(
+          setup.tokenMintA.publicKey,
+          setup.producerTokenAccountA,
+          setup.producer.publicKey,
+          3,
+          undefined,
+          tokenProgram,
+        ),
+        ...[setup.consumerTokenAccountB, secondConsumerAccounts.consumerTokenAccountB].map((ata) =>
+          createMintToInstruction(setup.tokenMintB.publicKey, ata, provider.publicKey, 10, undefined, tokenProgram),
+        ),
+      ]
+
+      await provider.sendAndConfirm(tx, [setup.producer, setup.tokenMintA, setup.tokenMintB]).then(log)
+    })
+
+    it('CreateOffer: three lots at a unit price', async () => {
+      await program.methods
+        .open(new BN(3), new BN(2), null, null)
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('AcceptOffer: first consumer takes two lots', async () => {
+      await program.methods
+        .accept(new BN(2), [], 0, null, null)
+        .accounts({ ...setup.accounts })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('ConfirmOffer: offer stays open for the remainder', async () => {
+      await program.methods
+        .confirm()
+        .accounts({
+          ...setup.accounts,
+          initiator: setup.consumer.publicKey,
+          receipt: await receiptFor(setup.producer.publicKey),
+        })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const offer = await program.account.offer.fetch(setup.offer)
+      expect(offer.tokenARemainingAmount.toNumber()).to.equal(1)
+      expect(offer.status).to.have.property('created')
+    })
+
+    it('RefundOffer: only the producer can refund the reopened offer', async () => {
+      try {
+        await program.methods
+          .refund()
+          .accounts({ ...setup.accounts, vaultTokenAccountB: null, initiator: setup.consumer.publicKey })
+          .signers([setup.consumer])
+          .rpc()
+        expect.fail('consumer refunded a reopened offer')
+      } catch (err) {
+        expect(err.toString()).to.include('UnauthorizedRefund')
+      }
+    })
+
+    it('AcceptOffer: second consumer takes the last lot', async () => {
+      await program.methods
+        .accept(new BN(1), [], 0, null, null)
+        .accounts({ ...secondConsumerAccounts })
+        .signers([secondConsumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('ConfirmOffer: offer closes once filled', async () => {
+      await program.methods
+        .confirm()
+        .accounts({
+          ...secondConsumerAccounts,
+          initiator: secondConsumer.publicKey,
+          receipt: await receiptFor(setup.producer.publicKey),
+        })
+        .signers([secondConsumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      expect(await connection.getAccountInfo(setup.offer)).to.be.null
+    })
+  })
+
+  describe('Private Offer Flow', () => {
+    const setup = createSetup()
+    const stranger = Keypair.generate()
+    const distributor = Keypair.generate()
+
+    const leaves = [setup.consumer, distributor].map((a) => Buffer.from(keccak_256(a.publicKey.toBuffer())))
+    const root = Array.from(keccak_256(Buffer.concat([...leaves].sort(Buffer.compare))))
+
+    it('Initialization', async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection)
+      let tx = new Transaction()
+
+      tx.instructions = [
+        ...[setup.producer, setup.consumer, stranger].map((a) =>
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: a.publicKey,
+            lamports: 0.1 * LAMPORTS_PER_SOL,
+          }),
+        ),
+        ...[setup.tokenMintA, setup.tokenMintB].map((m) =>
+          SystemProgram.createAccount({
+            fromPubkey: provider.publicKey,
+            newAccountPubkey: m.publicKey,
+            lamports,
+            space: MINT_SIZE,
+            programId: tokenProgram,
+          }),
+        ),
+        ...[
+          { mint: setup.tokenMintA.publicKey, authority: setup.producer.publicKey, ata: setup.producerTokenAccountA },
+          { mint: setup.tokenMintB.publicKey, authority: setup.consumer.publicKey, ata: setup.consumerTokenAccountB },
+        ].flatMap((x) => [
+          createInitializeMint2Instruction(x.mint, 6, x.authority, null, tokenProgram),
+          createAssociatedTokenAccountIdempotentInstruction(
+            provider.publicKey,
+            x.ata,
+            x.authority,
+            x.mint,
+            tokenProgram,
+          ),
+          createMintToInstruction(x.mint, x.ata, x.authority, 2, undefined, tokenProgram),
+        ]),
+      ]
+
+      await provider.sendAndConfirm(tx, [setup.producer, setup.consumer, setup.tokenMintA, setup.tokenMintB]).then(log)
+    })
+
+    it('CreateOffer: restricted to a Merkle allowlist', async () => {
+      await program.methods
+        .open(new BN(1), new BN(1), { merkleRoot: { 0: root } }, null)
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('AcceptOffer: rejects buyers outside the allowlist', async () => {
+      try {
+        await program.methods
+          .accept(new BN(1), [], 0, null, null)
+          .accounts({ ...setup.accounts, consumer: stranger.publicKey, consumerTokenAccountB: null })
+          .signers([stranger])
+          .rpc()
+        expect.fail('offer accepted by a buyer outside the allowlist')
+      } catch (err) {
+        expect(err.toString()).to.include('Unauthorized')
+      }
+    })
+
+    it('AcceptOffer: allowlisted consumer proves membership', async () => {
+      await program.methods
+        .accept(new BN(1), [Array.from(leaves[1])], 0, null, null)
+        .accounts({ ...setup.accounts })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const offer = await program.account.offer.fetch(setup.offer)
+      expect(offer.status).to.have.property('accepted')
+    })
+  })
+
+  describe('Amend Flow', () => {
+    const setup = createSetup()
+
+    it('Initialization', async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection)
+      let tx = new Transaction()
+
+      tx.instructions = [
+        ...[setup.producer, setup.consumer].map((a) =>
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: a.publicKey,
+            lamports: 0.1 * LAMPORTS_PER_SOL,
+          }),
+        ),
+        ...[setup.tokenMintA, setup.tokenMintB].map((m) =>
+          SystemProgram.createAccount({
+            fromPubkey: provider.publicKey,
+            newAccountPubkey: m.publicKey,
+            lamports,
+            space: MINT_SIZE,
+            programId: tokenProgram,
+          }),
+        ),
+        ...[
+          { mint: setup.tokenMintA.publicKey, authority: setup.producer.publicKey, ata: setup.producerTokenAccountA },
+          { mint: setup.tokenMintB.publicKey, authority: setup.consumer.publicKey, ata: setup.consumerTokenAccountB },
+        ].flatMap((x) => [
+          createInitializeMint2Instruction(x.mint, 6, x.authority, null, tokenProgram),
+          createAssociatedTokenAccountIdempotentInstruction(
+            provider.publicKey,
+            x.ata,
+            x.authority,
+            x.mint,
+            tokenProgram,
+          ),
+          createMintToInstruction(x.mint, x.ata, x.authority, 2, undefined, tokenProgram),
+        ]),
+      ]
+
+      await provider.sendAndConfirm(tx, [setup.producer, setup.consumer, setup.tokenMintA, setup.tokenMintB]).then(log)
+    })
+
+    it('CreateOffer', async () => {
+      await program.methods
+        .open(new BN(1), new BN(1), null, null)
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('AmendOffer: raises the price and bumps the version', async () => {
+      await program.methods
+        .amend({
+          tokenBDesiredAmount: new BN(2),
+          allowlist: null,
+          expiresAt: new BN(Math.floor(Date.now() / 1000) + 3600),
+          shipmentReleaseBps: 0,
+          attestor: null,
+          coldChain: null,
+          requiresCustody: false,
+          geofence: null,
+          barter: null,
+        })
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const offer = await program.account.offer.fetch(setup.offer)
+      expect(offer.version).to.equal(1)
+      expect(offer.tokenBDesiredAmount.toNumber()).to.equal(2)
+    })
+
+    it('AcceptOffer: rejects a stale version', async () => {
+      try {
+        await program.methods.accept(new BN(1), [], 0, null, null).accounts({ ...setup.accounts }).signers([setup.consumer]).rpc()
+        expect.fail('offer accepted at a stale version')
+      } catch (err) {
+        expect(err.toString()).to.include('OfferVersionMismatch')
+      }
+    })
+
+    it('AcceptOffer: pins the amended version', async () => {
+      await program.methods
+        .accept(new BN(1), [], 1, null, null)
+        .accounts({ ...setup.accounts })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const balance = await connection.getTokenAccountBalance(setup.vaultTokenAccountB)
+      expect(balance.value.amount).to.equal('2')
+    })
+  })
+
+  describe('Delivery Code Flow', () => {
+    const setup = createSetup()
+    const code = randomBytes(16)
+    const setup = createSetup()
+
+    it('Initialization', async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection)
+      let tx = new Transaction()
+
+      tx.instructions = [
+        ...[setup.producer, setup.consumer].map((a) =>
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: a.publicKey,
+            lamports: 0.1 * LAMPORTS_PER_SOL,
+          }),
+        ),
+        ...[setup.tokenMintA, setup.tokenMintB].map((m) =>
+          SystemProgram.createAccount({
+            fromPubkey: provider.publicKey,
+            newAccountPubkey: m.publicKey,
+            lamports,
+            space: MINT_SIZE,
+            programId: tokenProgram,
+          }),
+        ),
+        ...[
+          { mint: setup.tokenMintA.publicKey, authority: setup.producer.publicKey, ata: setup.producerTokenAccountA },
+          { mint: setup.tokenMintB.publicKey, authority: setup.consumer.publicKey, ata: setup.consumerTokenAccountB },
+        ].flatMap((x) => [
+          createInitializeMint2Instruction(x.mint, 6, x.authority, null, tokenProgram),
+          createAssociatedTokenAccountIdempotentInstruction(
+            provider.publicKey,
+            x.ata,
+            x.authority,
+            x.mint,
+            tokenProgram,
+          ),
+          createMintToInstruction(x.mint, x.ata, x.authority, 2, undefined, tokenProgram),
+        ]),
+      ]
+
+      await provider.sendAndConfirm(tx, [setup.producer, setup.consumer, setup.tokenMintA, setup.tokenMintB]).then(log)
+    })
+
+    it('CreateOffer', async () => {
+      await program.methods
+        .open(new BN(1), new BN(1), null, null)
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('AcceptOffer: consumer locks the offer to a delivery code', async () => {
+      await program.methods
+        .accept(new BN(1), [], 0, Array.from(keccak_256(code)), null)
+        .accounts({ ...setup.accounts })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('RevealDeliveryCode: rejects a wrong code', async () => {
+      try {
+        await program.methods
+          .revealDeliveryCode(randomBytes(16))
+          .accounts({ authority: setup.producer.publicKey, offer: setup.offer })
+          .signers([setup.producer])
+          .rpc()
+        expect.fail('wrong delivery code accepted')
+      } catch (err) {
+        expect(err.toString()).to.include('InvalidDeliveryCode')
+      }
+    })
+
+    it('RevealDeliveryCode: producer settles at handover without the consumer', async () => {
+      await program.methods
+        .revealDeliveryCode(code)
+        .accounts({ authority: setup.producer.publicKey, offer: setup.offer })
+        .postInstructions([
+          await program.methods
+            .confirm()
+            .accounts({
+              ...setup.accounts,
+              initiator: setup.producer.publicKey,
+              receipt: await receiptFor(setup.producer.publicKey),
+            })
+            .instruction(),
+        ])
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const balance = await connection.getTokenAccountBalance(setup.producerTokenAccountB)
+      expect(balance.value.amount).to.equal('1')
+      expect(await connection.getAccountInfo(setup.offer)).to.be.null
+    })
+  })
+
+  describe('Shipping Details Flow', () => {
+    const setup = createSetup()
+    const shippingDetails = {
+      ephemeralPublicKey: Array.from(randomBytes(32)),
+      nonce: Array.from(randomBytes(24)),
+      ciphertext: randomBytes(96),
+    }
+
+    it('Initialization', async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection)
+      let tx = new Transaction()
+
+      tx.instructions = [
+        ...[setup.producer, setup.consumer].map((a) =>
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: a.publicKey,
+            lamports: 0.1 * LAMPORTS_PER_SOL,
+          }),
+        ),
+        ...[setup.tokenMintA, setup.tokenMintB].map((m) =>
+          SystemProgram.createAccount({
+            fromPubkey: provider.publicKey,
+            newAccountPubkey: m.publicKey,
+            lamports,
+            space: MINT_SIZE,
+            programId: tokenProgram,
+          }),
+        ),
+        ...[
+          { mint: setup.tokenMintA.publicKey, authority: setup.producer.publicKey, ata: setup.producerTokenAccountA },
+          { mint: setup.tokenMintB.publicKey, authority: setup.consumer.publicKey, ata: setup.consumerTokenAccountB },
+        ].flatMap((x) => [
+          createInitializeMint2Instruction(x.mint, 6, x.authority, null, tokenProgram),
+          createAssociatedTokenAccountIdempotentInstruction(
+            provider.publicKey,
+            x.ata,
+            x.authority,
+            x.mint,
+            tokenProgram,
+          ),
+          createMintToInstruction(x.mint, x.ata, x.authority, 2, undefined, tokenProgram),
+        ]),
+      ]
+
+      await provider.sendAndConfirm(tx, [setup.producer, setup.consumer, setup.tokenMintA, setup.tokenMintB]).then(log)
+    })
+
+    it('CreateOffer', async () => {
+      await program.methods
+        .open(new BN(1), new BN(1), null, null)
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('AcceptOffer: grows the offer to hold the sealed shipping address', async () => {
+      const before = await connection.getAccountInfo(setup.offer)
+
+      await program.methods
+        .accept(new BN(1), [], 0, null, shippingDetails)
+        .accounts({ ...setup.accounts })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const after = await connection.getAccountInfo(setup.offer)
+      expect(after.data.length).to.equal(before.data.length + shippingDetails.ciphertext.length)
+
+      const offer = await program.account.offer.fetch(setup.offer)
+      expect(Buffer.from(offer.shippingDetails.ciphertext)).to.deep.equal(shippingDetails.ciphertext)
+    })
+
+    it('RefundOffer: wipes the shipping address with the offer', async () => {
+      await program.methods
+        .refund()
+        .accounts({ ...setup.accounts, initiator: setup.consumer.publicKey })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      expect(await connection.getAccountInfo(setup.offer)).to.be.null
+    })
+  })
+
+  describe('Listing Flow', () => {
+    const setup = createSetup()
+
+
+    it('Initialization', async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection)
+      let tx = new Transaction()
+
+      tx.instructions = [
+        ...[setup.producer, setup.consumer].map((a) =>
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: a.publicKey,
+            lamports: 0.1 * LAMPORTS_PER_SOL,
+          }),
+        ),
+        ...[setup.tokenMintA, setup.tokenMintB].map((m) =>
+          SystemProgram.createAccount({
+            fromPubkey: provider.publicKey,
+            newAccountPubkey: m.publicKey,
+            lamports,
+            space: MINT_SIZE,
+            programId: tokenProgram,
+          }),
+        ),
+        ...[
+          { mint: setup.tokenMintA.publicKey, authority: setup.producer.publicKey, ata: setup.producerTokenAccountA },
+          { mint: setup.tokenMintB.publicKey, authority: setup.consumer.publicKey, ata: setup.consumerTokenAccountB },
+        ].flatMap((x) => [
+          createInitializeMint2Instruction(x.mint, 6, x.authority, null, tokenProgram),
+          createAssociatedTokenAccountIdempotentInstruction(
+            provider.publicKey,
+            x.ata,
+            x.authority,
+            x.mint,
+            tokenProgram,
+          ),
+          createMintToInstruction(x.mint, x.ata, x.authority, 2, undefined, tokenProgram),
+        ]),
+      ]
+
+      await provider.sendAndConfirm(tx, [setup.producer, setup.consumer, setup.tokenMintA, setup.tokenMintB]).then(log)
+    })
+
+    it('CreateOffer: lists the mint', async () => {
+      await program.methods
+        .open(new BN(1), new BN(1), null, new BN(Math.floor(Date.now() / 1000) + 2))
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const listing = await program.account.listing.fetch(setup.listing)
+      expect(listing.offer.toBase58()).to.equal(setup.offer.toBase58())
+    })
+
+    it('CreateOffer: the same mint cannot be listed twice', async () => {
+      const secondOffer = PublicKey.findProgramAddressSync(
+        [Buffer.from('offer'), setup.producer.publicKey.toBuffer(), new BN(1).toArrayLike(Buffer, 'le', 8)],
+        program.programId,
+      )[0]
+
+      try {
+        await program.methods
+          .open(new BN(1), new BN(1), null, null)
+          .accounts({
+            ...setup.accounts,
+            offer: secondOffer,
+            vaultTokenAccountA: getAssociatedTokenAddressSync(setup.tokenMintA.publicKey, secondOffer, true, tokenProgram),
+          })
+          .signers([setup.producer])
+          .rpc()
+        expect.fail('mint listed twice')
+      } catch (err) {
+        expect(err.toString()).to.include('already in use')
+      }
+    })
+
+    it('DelistOffer: anyone removes the listing once the offer expires', async () => {
+      await new Promise((resolve) => setTimeout(resolve, 3000))
+
+      await program.methods
+        .delist()
+        .accounts({ producer: setup.producer.publicKey, offer: setup.offer, listing: setup.listing })
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      expect(await connection.getAccountInfo(setup.listing)).to.be.null
+    })
+
+    it('RefundOffer: refunds a delisted offer', async () => {
+      await program.methods
+        .refund()
+        .accounts({ ...setup.accounts, listing: null, initiator: setup.producer.publicKey })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      expect(await connection.getAccountInfo(setup.offer)).to.be.null
+    })
+  })
+
+  describe('Instant Buy Flow', () => {
+    const setup = createSetup()
+    const amendArgs = {
+      tokenBDesiredAmount: new BN(1),
+      allowlist: null,
+      expiresAt: null,
+      shipmentReleaseBps: 0,
+      attestor: null,
+      coldChain: null,
+      geofence: null,
+      barter: null,
+    }
+
+    it('Initialization', async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection)
+      let tx = new Transaction()
+
+      tx.instructions = [
+        ...[setup.producer, setup.consumer].map((a) =>
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: a.publicKey,
+            lamports: 0.1 * LAMPORTS_PER_SOL,
+          }),
+        ),
+        ...[setup.tokenMintA, setup.tokenMintB].map((m) =>
+          SystemProgram.createAccount({
+            fromPubkey: provider.publicKey,
+            newAccountPubkey: m.publicKey,
+            lamports,
+            space: MINT_SIZE,
+            programId: tokenProgram,
+          }),
+        ),
+        ...[
+          { mint: setup.tokenMintA.publicKey, authority: setup.producer.publicKey, ata: setup.producerTokenAccountA },
+          { mint: setup.tokenMintB.publicKey, authority: setup.consumer.publicKey, ata: setup.consumerTokenAccountB },
+        ].flatMap((x) => [
+          createInitializeMint2Instruction(x.mint, 6, x.authority, null, tokenProgram),
+          createAssociatedTokenAccountIdempotentInstruction(
+            provider.publicKey,
+            x.ata,
+            x.authority,
+            x.mint,
+            tokenProgram,
+          ),
+          createMintToInstruction(x.mint, x.ata, x.authority, 2, undefined, tokenProgram),
+        ]),
+      ]
+
+      await provider.sendAndConfirm(tx, [setup.producer, setup.consumer, setup.tokenMintA, setup.tokenMintB]).then(log)
+    })
+
+    it('CreateOffer: two lots at a unit price', async () => {
+      await program.methods
+        .open(new BN(2), new BN(1), null, null)
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('BuyOffer: pays the producer and delivers one lot at once', async () => {
+      const signature = await program.methods
+        .buy(new BN(1), [], 0)
+        .accounts({ ...setup.accounts, receipt: await receiptFor(setup.producer.publicKey) })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const offer = await program.account.offer.fetch(setup.offer)
+      expect(offer.tokenARemainingAmount.toNumber()).to.equal(1)
+      expect(offer.status).to.have.property('created')
+
+      const balance = await connection.getTokenAccountBalance(setup.producerTokenAccountB)
+      expect(balance.value.amount).to.equal('1')
+
+      const [bought] = await eventsOf(signature)
+      expect(bought.name).to.equal('offerBought')
+      expect(bought.data.invoiceNumber.toNumber()).to.equal(1)
+    })
+
+    it('BuyOffer: refused once the offer has delivery terms', async () => {
+      await program.methods
+        .amend({ ...amendArgs, requiresCustody: true })
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      try {
+        await program.methods
+          .buy(new BN(1), [], 1)
+          .accounts({ ...setup.accounts, receipt: await receiptFor(setup.producer.publicKey) })
+          .signers([setup.consumer])
+          .rpc()
+        expect.fail('bought an offer with delivery terms')
+      } catch (err) {
+        expect(err.toString()).to.include('DeliveryRequired')
+      }
+    })
+
+    it('BuyOffer: the last lot closes the offer and its listing', async () => {
+      await program.methods
+        .amend({ ...amendArgs, requiresCustody: false })
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      await program.methods
+        .buy(new BN(1), [], 2)
+        .accounts({ ...setup.accounts, receipt: await receiptFor(setup.producer.publicKey) })
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      expect(await connection.getAccountInfo(setup.offer)).to.be.null
+      expect(await connection.getAccountInfo(setup.listing)).to.be.null
+
+      const balance = await connection.getTokenAccountBalance(setup.consumerTokenAccountA)
+      expect(balance.value.amount).to.equal('2')
+    })
+  })
+
+  describe('Barter Flow', () => {
+    const setup = createSetup()
+    const tokenMintC = Keypair.generate()
+    const consumerTokenAccountC = getAssociatedTokenAddressSync(
+      tokenMintC.publicKey,
+      setup.consumer.publicKey,
+      false,
+      tokenProgram,
+    )
+    const vaultTokenAccountC = getAssociatedTokenAddressSync(tokenMintC.publicKey, setup.offer, true, tokenProgram)
+    const producerTokenAccountC = getAssociatedTokenAddressSync(
+      tokenMintC.publicKey,
+      setup.producer.publicKey,
+      false,
+      tokenProgram,
+    )
+
+    it('Initialization', async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection)
+      let tx = new Transaction()
+
+      tx.instructions = [
+        ...[setup.producer, setup.consumer].map((a) =>
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: a.publicKey,
+            lamports: 0.1 * LAMPORTS_PER_SOL,
+          }),
+        ),
+        ...[setup.tokenMintA, setup.tokenMintB, tokenMintC].map((m) =>
+          SystemProgram.createAccount({
+            fromPubkey: provider.publicKey,
+            newAccountPubkey: m.publicKey,
+            lamports,
+            space: MINT_SIZE,
+            programId: tokenProgram,
+          }),
+        ),
+        ...[
+          {
+            mint: setup.tokenMintA.publicKey,
+            decimals: 0,
+            amount: 1,
+            authority: setup.producer.publicKey,
+            ata: setup.producerTokenAccountA,
+          },
+          {
+            mint: setup.tokenMintB.publicKey,
+            decimals: 6,
+            amount: 2,
+            authority: setup.consumer.publicKey,
+            ata: setup.consumerTokenAccountB,
+          },
+          {
+            mint: tokenMintC.publicKey,
+            decimals: 0,
+            amount: 1,
+            authority: setup.consumer.publicKey,
+            ata: consumerTokenAccountC,
+          },
+        ].flatMap((x) => [
+          createInitializeMint2Instruction(x.mint, x.decimals, x.authority, null, tokenProgram),
+          createAssociatedTokenAccountIdempotentInstruction(
+            provider.publicKey,
+            x.ata,
+            x.authority,
+            x.mint,
+            tokenProgram,
+          ),
+          createMintToInstruction(x.mint, x.ata, x.authority, x.amount, undefined, tokenProgram),
+        ]),
+      ]
+
+      await provider
+        .sendAndConfirm(tx, [setup.producer, setup.consumer, setup.tokenMintA, setup.tokenMintB, tokenMintC])
+        .then(log)
+    })
+
+    it('CreateOffer', async () => {
+      await program.methods
+        .open(new BN(1), new BN(1), null, null)
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('AmendOffer: asks for a named bottle plus a cash top-up', async () => {
+      await program.methods
+        .amend({
+          tokenBDesiredAmount: new BN(1),
+          allowlist: null,
+          expiresAt: null,
+          shipmentReleaseBps: 0,
+          attestor: null,
+          coldChain: null,
+          requiresCustody: false,
+          geofence: null,
+          barter: { mints: [tokenMintC.publicKey], collection: null, collectionCount: 0 },
+        })
+        .accounts({ ...setup.accounts })
+        .signers([setup.producer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    })
+
+    it('AcceptOffer: rejects an acceptance without the wanted NFT', async () => {
+      try {
+        await program.methods
+          .accept(new BN(1), [], 1, null, null)
+          .accounts({ ...setup.accounts })
+          .signers([setup.consumer])
+          .rpc()
+        expect.fail('barter offer accepted without the wanted NFT')
+      } catch (err) {
+        expect(err.toString()).to.include('InvalidBarterAccounts')
+      }
+    })
+
+    it('AcceptOffer: escrows the wanted NFT with the top-up', async () => {
+      await program.methods
+        .accept(new BN(1), [], 1, null, null)
+        .accounts({ ...setup.accounts })
+        .remainingAccounts([
+          { pubkey: tokenMintC.publicKey, isSigner: false, isWritable: false },
+          { pubkey: vaultTokenAccountC, isSigner: false, isWritable: true },
+          { pubkey: consumerTokenAccountC, isSigner: false, isWritable: true },
+        ])
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      const offer = await program.account.offer.fetch(setup.offer)
+      expect(offer.barterMints.map((mint) => mint.toBase58())).to.deep.equal([tokenMintC.publicKey.toBase58()])
+
+      const balance = await connection.getTokenAccountBalance(vaultTokenAccountC)
+      expect(balance.value.amount).to.equal('1')
+    })
+
+    it('ConfirmOffer: swaps the bottles and pays the top-up', async () => {
+      await program.methods
+        .confirm()
+        .accounts({
+          ...setup.accounts,
+          initiator: setup.consumer.publicKey,
+          receipt: await receiptFor(setup.producer.publicKey),
+        })
+        .remainingAccounts([
+          { pubkey: tokenMintC.publicKey, isSigner: false, isWritable: false },
+          { pubkey: vaultTokenAccountC, isSigner: false, isWritable: true },
+          { pubkey: producerTokenAccountC, isSigner: false, isWritable: true },
+        ])
+        .signers([setup.consumer])
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      expect(await connection.getAccountInfo(setup.offer)).to.be.null
+      expect(await connection.getAccountInfo(vaultTokenAccountC)).to.be.null
+
+      const balances = await Promise.all(
+        [producerTokenAccountC, setup.consumerTokenAccountA, setup.producerTokenAccountB].map((account) =>
+          connection.getTokenAccountBalance(account),
+        ),
+      )
+      expect(balances.map((balance) => balance.value.amount)).to.deep.equal(['1', '1', '1'])
+    })
+  })
+
+  describe('Batch Flow', () => {
+    const setups = [createSetup(), createSetup()]
+
+    it('Initialization', async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection)
+
+      for (const setup of setups) {
+        let tx = new Transaction()
+
+        tx.instructions = [
+          ...[setup.producer, setup.consumer].map((a) =>
+            SystemProgram.transfer({
+              fromPubkey: provider.publicKey,
+              toPubkey: a.publicKey,
+              lamports: 0.1 * LAMPORTS_PER_SOL,
+            }),
+          ),
+          ...[setup.tokenMintA, setup.tokenMintB].map((m) =>
+            SystemProgram.createAccount({
+              fromPubkey: provider.publicKey,
+              newAccountPubkey: m.publicKey,
+              lamports,
+              space: MINT_SIZE,
+              programId: tokenProgram,
+            }),
+          ),
+          ...[
+            { mint: setup.tokenMintA.publicKey, authority: setup.producer.publicKey, ata: setup.producerTokenAccountA },
+            { mint: setup.tokenMintB.publicKey, authority: setup.consumer.publicKey, ata: setup.consumerTokenAccountB },
+          ].flatMap((x) => [
+            createInitializeMint2Instruction(x.mint, 6, x.authority, null, tokenProgram),
+            createAssociatedTokenAccountIdempotentInstruction(
+              provider.publicKey,
+              x.ata,
+              x.authority,
+              x.mint,
+              tokenProgram,
+            ),
+            createMintToInstruction(x.mint, x.ata, x.authority, 2, undefined, tokenProgram),
+          ]),
+        ]
+
+        await provider
+          .sendAndConfirm(tx, [setup.producer, setup.consumer, setup.tokenMintA, setup.tokenMintB])
+          .then(log)
+      }
+    })
+
+    it('CreateOffer and AcceptOffer: one offer per producer', async () => {
+      for (const setup of setups) {
+        await program.methods
+          .open(new BN(1), new BN(1), null, null)
+          .accounts({ ...setup.accounts })
+          .signers([setup.producer])
+          .rpc()
+          .then(confirm)
+          .then(log)
+
+        await program.methods
+          .accept(new BN(1), [], 0, null, null)
+          .accounts({ ...setup.accounts })
+          .signers([setup.consumer])
+          .rpc()
+          .then(confirm)
+          .then(log)
+      }
+    })
+
+    it('ConfirmMany: settles every offer in one transaction', async () => {
+      // Each entry is laid out exactly like the accounts of a single confirm.
+      const entries = await Promise.all(
+        setups.map(async (setup) =>
+          program.methods
+            .confirm()
+            .accounts({
+              ...setup.accounts,
+              initiator: setup.consumer.publicKey,
+              receipt: await receiptFor(setup.producer.publicKey),
+            })
+            .instruction(),
+        ),
+      )
+
+      const signature = await program.methods
+        .confirmMany(Buffer.from(entries.map(() => 0)))
+        .accounts({ initiator: setups[0].consumer.publicKey })
+        .remainingAccounts(entries.flatMap((entry) => entry.keys))
+        .signers(setups.map((setup) => setup.consumer))
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      for (const setup of setups) {
+        expect(await connection.getAccountInfo(setup.offer)).to.be.null
+        const balance = await connection.getTokenAccountBalance(setup.consumerTokenAccountA)
+        expect(balance.value.amount).to.equal('1')
+      }
+
+      const events = await eventsOf(signature)
+      expect(events.map((event) => event.name)).to.deep.equal(['offerConfirmed', 'offerConfirmed'])
+      expect(events.map((event) => event.data.offer.toBase58())).to.deep.equal(
+        setups.map((setup) => setup.offer.toBase58()),
+      )
+    })
+
+    it('ConfirmMany: rejects trailing accounts', async () => {
+      try {
+        await program.methods
+          .confirmMany(Buffer.from([]))
+          .accounts({ initiator: setups[0].consumer.publicKey })
+          .remainingAccounts([{ pubkey: setups[0].offer, isSigner: false, isWritable: false }])
+          .signers([setups[0].consumer])
+          .rpc()
+        expect.fail('trailing accounts accepted')
+      } catch (err) {
+        expect(err.toString()).to.include('InvalidBatchAccounts')
+      }
+    })
+  })
+
+  describe('Batch Refund Flow', () => {
+    const setups = [createSetup(), createSetup()]
+
+    it('Initialization', async () => {
+      let lamports = await getMinimumBalanceForRentExemptMint(connection)
+
+      for (const setup of setups) {
+        let tx = new Transaction()
+
+        tx.instructions = [
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: setup.producer.publicKey,
+            lamports: 0.1 * LAMPORTS_PER_SOL,
+          }),
+          ...[setup.tokenMintA, setup.tokenMintB].map((m) =>
+            SystemProgram.createAccount({
+              fromPubkey: provider.publicKey,
+              newAccountPubkey: m.publicKey,
+              lamports,
+              space: MINT_SIZE,
+              programId: tokenProgram,
+            }),
+          ),
+          createInitializeMint2Instruction(setup.tokenMintA.publicKey, 0, setup.producer.publicKey, null, tokenProgram),
+          createInitializeMint2Instruction(setup.tokenMintB.publicKey, 6, setup.consumer.publicKey, null, tokenProgram),
+          createAssociatedTokenAccountIdempotentInstruction(
+            provider.publicKey,
+            setup.producerTokenAccountA,
+            setup.producer.publicKey,
+            setup.tokenMintA.publicKey,
+            tokenProgram,
+          ),
+          createMintToInstruction(
+            setup.tokenMintA.publicKey,
+            setup.producerTokenAccountA,
+            setup.producer.publicKey,
+            1,
+            undefined,
+            tokenProgram,
+          ),
+        ]
+
+        await provider.sendAndConfirm(tx, [setup.producer, setup.tokenMintA, setup.tokenMintB]).then(log)
+
+        await program.methods
+          .open(new BN(1), new BN(1), null, null)
+          .accounts({ ...setup.accounts })
+          .signers([setup.producer])
+          .rpc()
+          .then(confirm)
+          .then(log)
+      }
+    })
+
+    it('RefundMany: returns every NFT to its producer', async () => {
+      const entries = await Promise.all(
+        setups.map((setup) =>
+          program.methods
+            .refund()
+            .accounts({
+              ...setup.accounts,
+              consumerTokenAccountB: null,
+              vaultTokenAccountB: null,
+              initiator: setup.producer.publicKey,
+            })
+            .instruction(),
+        ),
+      )
+
+      const signature = await program.methods
+        .refundMany(Buffer.from(entries.map(() => 0)))
+        .accounts({ initiator: setups[0].producer.publicKey })
+        .remainingAccounts(entries.flatMap((entry) => entry.keys))
+        .signers(setups.map((setup) => setup.producer))
+        .rpc()
+        .then(confirm)
+        .then(log)
+
+      for (const setup of setups) {
+        expect(await connection.getAccountInfo(setup.offer)).to.be.null
+        const balance = await connection.getTokenAccountBalance(setup.producerTokenAccountA)
+        expect(balance.value.amount).to.equal('1')
+      }
+
+      const events = await eventsOf(signature)
+      expect(events.map((event) => event.name)).to.deep.equal(['offerRefunded', 'offerRefunded'])
+    })
+  })
 })
@@ -116,6 +116,7 @@ describe('grpx-protocols/factory', async () => {
         description: '',
         uri: '',
         sellerFeeBasisPoints: 500,
+        attributes: null,
       })
       .accountsPartial({
         owner: wallet.publicKey,
@@ -166,6 +167,7 @@ describe('grpx-protocols/factory', async () => {
         description: '',
         uri: '',
         sellerFeeBasisPoints: 100,
+        attributes: [{ traitType: 'vintage', value: '2015' }],
       })
       .accountsPartial({
         owner: wallet.publicKey,
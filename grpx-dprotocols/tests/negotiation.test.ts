@@ -0,0 +1,264 @@
+import * as anchor from '@coral-xyz/anchor'
+import { Program } from '@coral-xyz/anchor'
+
+import { GrpxDprotocols } from '../target/types/grpx_dprotocols'
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from '@solana/web3.js'
+import {
+  MINT_SIZE,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMint2Instruction,
+  createMintToInstruction,
+  getMinimumBalanceForRentExemptMint,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
+} from '@solana/spl-token'
+import { BN } from 'bn.js'
+import { expect } from 'chai'
+
+const IDL = require('../target/idl/grpx_dprotocols.json')
+
+describe('grpx-dprotocols/negotiation', () => {
+  anchor.setProvider(anchor.AnchorProvider.env())
+  const provider = anchor.getProvider()
+  const connection = provider.connection
+  const program = new Program<GrpxDprotocols>(IDL, provider)
+  const tokenProgram = TOKEN_2022_PROGRAM_ID
+
+  const confirm = async (signature: string): Promise<string> => {
+    const block = await connection.getLatestBlockhash()
+
+    await connection.confirmTransaction({
+      signature,
+      ...block,
+    })
+
+    return signature
+  }
+
+  const log = async (signature: string): Promise<string> => {
+    console.log(
+      `Your transaction signature: https://explorer.solana.com/transaction/${signature}?cluster=${
+        connection.rpcEndpoint.split('.')[1]
+      }`,
+    )
+
+    return signature
+  }
+
+  const receiptFor = async (producer: PublicKey): Promise<PublicKey> => {
+    const ledger = PublicKey.findProgramAddressSync([Buffer.from('ledger'), producer.toBuffer()], program.programId)[0]
+    const invoiceCount = (await program.account.producerLedger.fetchNullable(ledger))?.invoiceCount ?? new BN(0)
+
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('receipt'), producer.toBuffer(), invoiceCount.addn(1).toArrayLike(Buffer, 'le', 8)],
+      program.programId,
+    )[0]
+  }
+
+  const [producer, consumer, tokenMintA, tokenMintB] = Array.from({ length: 4 }, () => Keypair.generate())
+
+  const [producerTokenAccountA, producerTokenAccountB, consumerTokenAccountA, consumerTokenAccountB] = [
+    producer,
+    consumer,
+  ]
+    .map((a) =>
+      [tokenMintA, tokenMintB].map((m) => getAssociatedTokenAddressSync(m.publicKey, a.publicKey, false, tokenProgram)),
+    )
+    .flat()
+
+  const id = new BN(0)
+  const offer = PublicKey.findProgramAddressSync(
+    [Buffer.from('offer'), producer.publicKey.toBuffer(), id.toArrayLike(Buffer, 'le', 8)],
+    program.programId,
+  )[0]
+  const counterOffer = PublicKey.findProgramAddressSync(
+    [Buffer.from('counter_offer'), offer.toBuffer(), consumer.publicKey.toBuffer()],
+    program.programId,
+  )[0]
+
+  const accounts = {
+    producer: producer.publicKey,
+    consumer: consumer.publicKey,
+    bidder: consumer.publicKey,
+    tokenMintA: tokenMintA.publicKey,
+    tokenMintB: tokenMintB.publicKey,
+    producerTokenAccountA,
+    producerTokenAccountB,
+    consumerTokenAccountA,
+    consumerTokenAccountB,
+    bidderTokenAccountB: consumerTokenAccountB,
+    offer,
+    vaultTokenAccountA: getAssociatedTokenAddressSync(tokenMintA.publicKey, offer, true, tokenProgram),
+    vaultTokenAccountB: getAssociatedTokenAddressSync(tokenMintB.publicKey, offer, true, tokenProgram),
+    listing: PublicKey.findProgramAddressSync(
+      [Buffer.from('listing'), tokenMintA.publicKey.toBuffer()],
+      program.programId,
+    )[0],
+    tokenProgram,
+  }
+
+  const counterAccounts = {
+    ...accounts,
+    counterOffer,
+    vaultTokenAccountB: getAssociatedTokenAddressSync(tokenMintB.publicKey, counterOffer, true, tokenProgram),
+  }
+
+  const expiresAt = () => new BN(Math.floor(Date.now() / 1000) + 3600)
+
+  it('Initialization', async () => {
+    let lamports = await getMinimumBalanceForRentExemptMint(connection)
+    let tx = new Transaction()
+
+    tx.instructions = [
+      ...[producer, consumer].map((a) =>
+        SystemProgram.transfer({
+          fromPubkey: provider.publicKey,
+          toPubkey: a.publicKey,
+          lamports: 0.1 * LAMPORTS_PER_SOL,
+        }),
+      ),
+      ...[tokenMintA, tokenMintB].map((m) =>
+        SystemProgram.createAccount({
+          fromPubkey: provider.publicKey,
+          newAccountPubkey: m.publicKey,
+          lamports,
+          space: MINT_SIZE,
+          programId: tokenProgram,
+        }),
+      ),
+      ...[
+        { mint: tokenMintA.publicKey, authority: producer.publicKey, ata: producerTokenAccountA, amount: 1 },
+        { mint: tokenMintB.publicKey, authority: consumer.publicKey, ata: consumerTokenAccountB, amount: 20 },
+      ].flatMap((x) => [
+        createInitializeMint2Instruction(x.mint, 0, x.authority, null, tokenProgram),
+        createAssociatedTokenAccountIdempotentInstruction(provider.publicKey, x.ata, x.authority, x.mint, tokenProgram),
+        createMintToInstruction(x.mint, x.ata, x.authority, x.amount, undefined, tokenProgram),
+      ]),
+    ]
+
+    await provider.sendAndConfirm(tx, [producer, consumer, tokenMintA, tokenMintB]).then(log)
+  })
+
+  it('CreateOffer: asks 10', async () => {
+    await program.methods
+      .open(new BN(1), new BN(10), null, null)
+      .accounts({ ...accounts })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+  })
+
+  it('ProposeCounterOffer: buyer counters at 7', async () => {
+    await program.methods
+      .proposeCounterOffer(new BN(7), expiresAt(), [])
+      .accounts({ ...counterAccounts })
+      .signers([consumer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    const balance = await connection.getTokenAccountBalance(consumerTokenAccountB)
+    expect(balance.value.amount).to.equal('13')
+  })
+
+  it('RejectCounterOffer: producer rejects and the buyer is refunded', async () => {
+    await program.methods
+      .rejectCounterOffer()
+      .accounts({ ...counterAccounts, initiator: producer.publicKey })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    const balance = await connection.getTokenAccountBalance(consumerTokenAccountB)
+    expect(balance.value.amount).to.equal('20')
+    expect(await connection.getAccountInfo(counterOffer)).to.be.null
+  })
+
+  it('ProposeCounterOffer: buyer counters at 8', async () => {
+    await program.methods
+      .proposeCounterOffer(new BN(8), expiresAt(), [])
+      .accounts({ ...counterAccounts })
+      .signers([consumer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+  })
+
+  const amendArgs = (barter: { mints: PublicKey[]; collection: null; collectionCount: number } | null) => ({
+    tokenBDesiredAmount: new BN(10),
+    allowlist: null,
+    expiresAt: null,
+    shipmentReleaseBps: 0,
+    attestor: null,
+    coldChain: null,
+    requiresCustody: false,
+    geofence: null,
+    barter,
+  })
+
+  it('AcceptCounterOffer: rejected once the offer asks for a barter', async () => {
+    await program.methods
+      .amend(amendArgs({ mints: [Keypair.generate().publicKey], collection: null, collectionCount: 0 }))
+      .accounts({ ...accounts })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    try {
+      await program.methods
+        .acceptCounterOffer()
+        .accounts({
+          ...accounts,
+          counterOffer,
+          counterVaultTokenAccountB: counterAccounts.vaultTokenAccountB,
+        })
+        .signers([producer])
+        .rpc()
+      expect.fail('counter-offer should not be accepted on a barter offer')
+    } catch (err) {
+      expect(err.toString()).to.include('BarterNotNegotiable')
+    }
+
+    await program.methods
+      .amend(amendArgs(null))
+      .accounts({ ...accounts })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+  })
+
+  it('AcceptCounterOffer: offer is accepted on the counter terms', async () => {
+    await program.methods
+      .acceptCounterOffer()
+      .accounts({
+        ...accounts,
+        counterOffer,
+        counterVaultTokenAccountB: counterAccounts.vaultTokenAccountB,
+      })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    const state = await program.account.offer.fetch(offer)
+    expect(state.status).to.have.property('accepted')
+    expect(state.tokenBDesiredAmount.toNumber()).to.equal(8)
+  })
+
+  it('ConfirmOffer: buyer confirms delivery', async () => {
+    await program.methods
+      .confirm()
+      .accounts({ ...accounts, initiator: consumer.publicKey, receipt: await receiptFor(producer.publicKey) })
+      .signers([consumer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    const balance = await connection.getTokenAccountBalance(producerTokenAccountB)
+    expect(balance.value.amount).to.equal('8')
+  })
+})
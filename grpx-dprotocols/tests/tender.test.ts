@@ -0,0 +1,341 @@
+import * as anchor from '@coral-xyz/anchor'
+import { Program } from '@coral-xyz/anchor'
+
+import { GrpxDprotocols } from '../target/types/grpx_dprotocols'
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction } from '@solana/web3.js'
+import {
+  MINT_SIZE,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMint2Instruction,
+  createMintToInstruction,
+  getMinimumBalanceForRentExemptMint,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
+} from '@solana/spl-token'
+import { keccak_256 } from '@noble/hashes/sha3'
+import { BN } from 'bn.js'
+import { randomBytes } from 'crypto'
+import { expect } from 'chai'
+
+const IDL = require('../target/idl/grpx_dprotocols.json')
+
+describe('grpx-dprotocols/tender', () => {
+  anchor.setProvider(anchor.AnchorProvider.env())
+  const provider = anchor.getProvider()
+  const connection = provider.connection
+  const program = new Program<GrpxDprotocols>(IDL, provider)
+  const tokenProgram = TOKEN_2022_PROGRAM_ID
+
+  const confirm = async (signature: string): Promise<string> => {
+    const block = await connection.getLatestBlockhash()
+
+    await connection.confirmTransaction({
+      signature,
+      ...block,
+    })
+
+    return signature
+  }
+
+  const log = async (signature: string): Promise<string> => {
+    console.log(
+      `Your transaction signature: https://explorer.solana.com/transaction/${signature}?cluster=${
+        connection.rpcEndpoint.split('.')[1]
+      }`,
+    )
+
+    return signature
+  }
+
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms))
+
+  const commitmentOf = (bidder: PublicKey, amount: BN, salt: Buffer) =>
+    Array.from(keccak_256(Buffer.concat([bidder.toBuffer(), amount.toArrayLike(Buffer, 'le', 8), salt])))
+
+  const [producer, alice, bob, carol, tokenMintA, tokenMintB] = Array.from({ length: 6 }, () => Keypair.generate())
+  const bidders = [alice, bob, carol]
+
+  const tokenAccount = (mint: Keypair, owner: PublicKey) =>
+    getAssociatedTokenAddressSync(mint.publicKey, owner, true, tokenProgram)
+
+  const id = new BN(randomBytes(8))
+  const tender = PublicKey.findProgramAddressSync(
+    [Buffer.from('tender'), producer.publicKey.toBuffer(), id.toArrayLike(Buffer, 'le', 8)],
+    program.programId,
+  )[0]
+  const sealedBid = (bidder: Keypair) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('sealed_bid'), tender.toBuffer(), bidder.publicKey.toBuffer()],
+      program.programId,
+    )[0]
+
+  const bids = new Map([
+    [alice, { amount: new BN(20), salt: randomBytes(32) }],
+    [bob, { amount: new BN(30), salt: randomBytes(32) }],
+    [carol, { amount: new BN(50), salt: randomBytes(32) }],
+  ])
+
+  const offerId = new BN(0)
+  const offer = PublicKey.findProgramAddressSync(
+    [Buffer.from('offer'), producer.publicKey.toBuffer(), offerId.toArrayLike(Buffer, 'le', 8)],
+    program.programId,
+  )[0]
+
+  const bidAccounts = (bidder: Keypair) => ({
+    bidder: bidder.publicKey,
+    tokenMintB: tokenMintB.publicKey,
+    bidderTokenAccountB: tokenAccount(tokenMintB, bidder.publicKey),
+    tender,
+    sealedBid: sealedBid(bidder),
+    vaultTokenAccountB: tokenAccount(tokenMintB, sealedBid(bidder)),
+    tokenProgram,
+  })
+
+  it('Initialization', async () => {
+    let lamports = await getMinimumBalanceForRentExemptMint(connection)
+    let tx = new Transaction()
+
+    tx.instructions = [
+      ...[producer, ...bidders].map((a) =>
+        SystemProgram.transfer({
+          fromPubkey: provider.publicKey,
+          toPubkey: a.publicKey,
+          lamports: 0.1 * LAMPORTS_PER_SOL,
+        }),
+      ),
+      ...[tokenMintA, tokenMintB].map((m) =>
+        SystemProgram.createAccount({
+          fromPubkey: provider.publicKey,
+          newAccountPubkey: m.publicKey,
+          lamports,
+          space: MINT_SIZE,
+          programId: tokenProgram,
+        }),
+      ),
+      createInitializeMint2Instruction(tokenMintA.publicKey, 0, producer.publicKey, null, tokenProgram),
+      createAssociatedTokenAccountIdempotentInstruction(
+        provider.publicKey,
+        tokenAccount(tokenMintA, producer.publicKey),
+        producer.publicKey,
+        tokenMintA.publicKey,
+        tokenProgram,
+      ),
+      createMintToInstruction(
+        tokenMintA.publicKey,
+        tokenAccount(tokenMintA, producer.publicKey),
+        producer.publicKey,
+        1,
+        undefined,
+        tokenProgram,
+      ),
+      createInitializeMint2Instruction(tokenMintB.publicKey, 0, provider.publicKey, null, tokenProgram),
+      ...[producer, ...bidders].map((a) =>
+        createAssociatedTokenAccountIdempotentInstruction(
+          provider.publicKey,
+          tokenAccount(tokenMintB, a.publicKey),
+          a.publicKey,
+          tokenMintB.publicKey,
+          tokenProgram,
+        ),
+      ),
+      ...bidders.map((a) =>
+        createMintToInstruction(
+          tokenMintB.publicKey,
+          tokenAccount(tokenMintB, a.publicKey),
+          provider.publicKey,
+          100,
+          undefined,
+          tokenProgram,
+        ),
+      ),
+    ]
+
+    await provider.sendAndConfirm(tx, [producer, tokenMintA, tokenMintB]).then(log)
+  })
+
+  it('OpenTender: second-price with a deposit', async () => {
+    const now = Math.floor(Date.now() / 1000)
+
+    await program.methods
+      .openTender(id, {
+        reservePrice: new BN(10),
+        deposit: new BN(5),
+        pricing: { secondPrice: {} },
+        commitEndsAt: new BN(now + 6),
+        revealEndsAt: new BN(now + 12),
+      })
+      .accounts({
+        producer: producer.publicKey,
+        tokenMintA: tokenMintA.publicKey,
+        tokenMintB: tokenMintB.publicKey,
+        producerTokenAccountA: tokenAccount(tokenMintA, producer.publicKey),
+        tender,
+        vaultTokenAccountA: tokenAccount(tokenMintA, tender),
+        tokenProgram,
+      })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+  })
+
+  it('CommitSealedBid: escrows the deposit', async () => {
+    for (const bidder of bidders) {
+      const { amount, salt } = bids.get(bidder)
+
+      await program.methods
+        .commitSealedBid(commitmentOf(bidder.publicKey, amount, salt))
+        .accounts(bidAccounts(bidder))
+        .signers([bidder])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    }
+
+    const balance = await connection.getTokenAccountBalance(tokenAccount(tokenMintB, alice.publicKey))
+    expect(balance.value.amount).to.equal('95')
+  })
+
+  it('RevealSealedBid: rejected during the commit phase', async () => {
+    const { amount, salt } = bids.get(alice)
+
+    try {
+      await program.methods
+        .revealSealedBid(amount, Array.from(salt))
+        .accounts(bidAccounts(alice))
+        .signers([alice])
+        .rpc()
+      expect.fail('bid revealed during the commit phase')
+    } catch (err) {
+      expect(err.toString()).to.include('InvalidTenderPhase')
+    }
+  })
+
+  it('RevealSealedBid: rejects a mismatched bid', async () => {
+    await sleep(7000)
+    const { salt } = bids.get(alice)
+
+    try {
+      await program.methods
+        .revealSealedBid(new BN(99), Array.from(salt))
+        .accounts(bidAccounts(alice))
+        .signers([alice])
+        .rpc()
+      expect.fail('mismatched reveal was accepted')
+    } catch (err) {
+      expect(err.toString()).to.include('InvalidReveal')
+    }
+  })
+
+  it('RevealSealedBid: alice and carol reveal, bob never does', async () => {
+    for (const bidder of [alice, carol]) {
+      const { amount, salt } = bids.get(bidder)
+
+      await program.methods
+        .revealSealedBid(amount, Array.from(salt))
+        .accounts(bidAccounts(bidder))
+        .signers([bidder])
+        .rpc()
+        .then(confirm)
+        .then(log)
+    }
+
+    const state = await program.account.tender.fetch(tender)
+    expect(state.highestBidder.toBase58()).to.equal(carol.publicKey.toBase58())
+    expect(state.secondBid.toNumber()).to.equal(20)
+  })
+
+  it('SettleTender: winner pays the second price', async () => {
+    await sleep(6000)
+
+    await program.methods
+      .settleTender()
+      .accounts({
+        initiator: producer.publicKey,
+        producer: producer.publicKey,
+        tokenMintA: tokenMintA.publicKey,
+        tokenMintB: tokenMintB.publicKey,
+        tender,
+        tenderVaultTokenAccountA: tokenAccount(tokenMintA, tender),
+        sealedBid: sealedBid(carol),
+        sealedBidVaultTokenAccountB: tokenAccount(tokenMintB, sealedBid(carol)),
+        offer,
+        vaultTokenAccountA: tokenAccount(tokenMintA, offer),
+        vaultTokenAccountB: tokenAccount(tokenMintB, offer),
+        tokenProgram,
+      })
+      .signers([producer])
+      .rpc()
+      .then(confirm)
+      .then(log)
+
+    const state = await program.account.offer.fetch(offer)
+    expect(state.consumer.toBase58()).to.equal(carol.publicKey.toBase58())
+    expect(state.tokenBDesiredAmount.toNumber()).to.equal(20)
+    expect(state.status).to.have.property('accepted')
+  })
+
+  it('RefundOffer: the winner cannot back out of an awarded offer', async () => {
+    try {
+      await program.methods
+        .refund()
+        .accounts({
+          producer: producer.publicKey,
+          consumer: carol.publicKey,
+          initiator: carol.publicKey,
+          tokenMintA: tokenMintA.publicKey,
+          tokenMintB: tokenMintB.publicKey,
+          producerTokenAccountA: tokenAccount(tokenMintA, producer.publicKey),
+          consumerTokenAccountB: tokenAccount(tokenMintB, carol.publicKey),
+          offer,
+          vaultTokenAccountA: tokenAccount(tokenMintA, offer),
+          vaultTokenAccountB: tokenAccount(tokenMintB, offer),
+          listing: null,
+          tokenProgram,
+        })
+        .signers([carol])
+        .rpc()
+      expect.fail('winner refunded an awarded offer')
+    } catch (err) {
+      expect(err.toString()).to.include('InvalidOfferStatus')
+    }
+  })
+
+  it('ReleaseSealedBid: rejected without the escrow vault', async () => {
+    try {
+      await program.methods
+        .releaseSealedBid()
+        .accounts({
+          ...bidAccounts(alice),
+          vaultTokenAccountB: null,
+          producer: producer.publicKey,
+          producerTokenAccountB: tokenAccount(tokenMintB, producer.publicKey),
+        })
+        .rpc()
+      expect.fail('sealed bid released without its vault')
+    } catch (err) {
+      expect(err.toString()).to.include('InvalidPaymentAccounts')
+    }
+  })
+
+  it('ReleaseSealedBid: refunds losers and the winner surplus, slashes the unrevealed deposit', async () => {
+    for (const bidder of bidders) {
+      await program.methods
+        .releaseSealedBid()
+        .accounts({
+          ...bidAccounts(bidder),
+          producer: producer.publicKey,
+          producerTokenAccountB: tokenAccount(tokenMintB, producer.publicKey),
+        })
+        .rpc()
+        .then(confirm)
+        .then(log)
+    }
+
+    const balances = await Promise.all(
+      [alice, bob, carol, producer].map((a) => connection.getTokenAccountBalance(tokenAccount(tokenMintB, a.publicKey))),
+    )
+    expect(balances.map((b) => b.value.amount)).to.deep.equal(['100', '95', '80', '5'])
+    expect(await connection.getAccountInfo(tender)).to.be.null
+  })
+})